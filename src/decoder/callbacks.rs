@@ -0,0 +1,256 @@
+#[cfg(feature = "flac")]
+use flac_sys::{FLAC__StreamDecoder, FLAC__StreamMetadata, FLAC__StreamDecoderErrorStatus, FLAC__Frame, FLAC__bool, FLAC__StreamDecoderReadStatus,
+               FLAC__StreamDecoderReadStatus_FLAC__STREAM_DECODER_READ_STATUS_CONTINUE,
+               FLAC__StreamDecoderReadStatus_FLAC__STREAM_DECODER_READ_STATUS_END_OF_STREAM,
+               FLAC__StreamDecoderReadStatus_FLAC__STREAM_DECODER_READ_STATUS_ABORT, FLAC__StreamDecoderSeekStatus,
+               FLAC__StreamDecoderSeekStatus_FLAC__STREAM_DECODER_SEEK_STATUS_OK, FLAC__StreamDecoderSeekStatus_FLAC__STREAM_DECODER_SEEK_STATUS_ERROR,
+               FLAC__StreamDecoderTellStatus, FLAC__StreamDecoderTellStatus_FLAC__STREAM_DECODER_TELL_STATUS_OK,
+               FLAC__StreamDecoderTellStatus_FLAC__STREAM_DECODER_TELL_STATUS_ERROR, FLAC__StreamDecoderLengthStatus,
+               FLAC__StreamDecoderLengthStatus_FLAC__STREAM_DECODER_LENGTH_STATUS_OK,
+               FLAC__StreamDecoderLengthStatus_FLAC__STREAM_DECODER_LENGTH_STATUS_ERROR, FLAC__StreamDecoderWriteStatus,
+               FLAC__StreamDecoderWriteStatus_FLAC__STREAM_DECODER_WRITE_STATUS_CONTINUE, FLAC__metadata_object_clone};
+
+#[cfg(feature = "libflac-nobuild")]
+use libflac_sys::{FLAC__StreamDecoder, FLAC__StreamMetadata, FLAC__StreamDecoderErrorStatus, FLAC__Frame, FLAC__bool, FLAC__StreamDecoderReadStatus,
+                  FLAC__STREAM_DECODER_READ_STATUS_CONTINUE as FLAC__StreamDecoderReadStatus_FLAC__STREAM_DECODER_READ_STATUS_CONTINUE,
+                  FLAC__STREAM_DECODER_READ_STATUS_END_OF_STREAM as FLAC__StreamDecoderReadStatus_FLAC__STREAM_DECODER_READ_STATUS_END_OF_STREAM,
+                  FLAC__STREAM_DECODER_READ_STATUS_ABORT as FLAC__StreamDecoderReadStatus_FLAC__STREAM_DECODER_READ_STATUS_ABORT,
+                  FLAC__StreamDecoderSeekStatus,
+                  FLAC__STREAM_DECODER_SEEK_STATUS_OK as FLAC__StreamDecoderSeekStatus_FLAC__STREAM_DECODER_SEEK_STATUS_OK,
+                  FLAC__STREAM_DECODER_SEEK_STATUS_ERROR as FLAC__StreamDecoderSeekStatus_FLAC__STREAM_DECODER_SEEK_STATUS_ERROR,
+                  FLAC__StreamDecoderTellStatus,
+                  FLAC__STREAM_DECODER_TELL_STATUS_OK as FLAC__StreamDecoderTellStatus_FLAC__STREAM_DECODER_TELL_STATUS_OK,
+                  FLAC__STREAM_DECODER_TELL_STATUS_ERROR as FLAC__StreamDecoderTellStatus_FLAC__STREAM_DECODER_TELL_STATUS_ERROR,
+                  FLAC__StreamDecoderLengthStatus,
+                  FLAC__STREAM_DECODER_LENGTH_STATUS_OK as FLAC__StreamDecoderLengthStatus_FLAC__STREAM_DECODER_LENGTH_STATUS_OK,
+                  FLAC__STREAM_DECODER_LENGTH_STATUS_ERROR as FLAC__StreamDecoderLengthStatus_FLAC__STREAM_DECODER_LENGTH_STATUS_ERROR,
+                  FLAC__StreamDecoderWriteStatus,
+                  FLAC__STREAM_DECODER_WRITE_STATUS_CONTINUE as FLAC__StreamDecoderWriteStatus_FLAC__STREAM_DECODER_WRITE_STATUS_CONTINUE,
+                  FLAC__metadata_object_clone};
+
+use super::FlacDecoderErrorStatus;
+use metadata::FlacMetadata;
+use std::panic::{self, AssertUnwindSafe};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::os::raw::c_void;
+use std::convert::TryFrom;
+use std::ptr::NonNull;
+use std::{fmt, slice};
+use std::any::Any;
+
+
+/// Rust-side state owned by an initialised [`FlacDecoder`](struct.FlacDecoder.html) and handed to the callbacks.
+///
+/// Boxed so its address remains stable for libFLAC, which holds on to it as `client_data`.
+///
+/// The source is borrowed for as long as enforced by the [`FlacDecoder`](struct.FlacDecoder.html).
+#[derive(Default)]
+pub struct DecoderCallbackData {
+    /// The source for [`init_read_seek()`](struct.FlacDecoderConfig.html#method.init_read_seek).
+    pub read_seek: Option<NonNull<ReadSeekWrapper<'static>>>,
+    /// Whether the source returned no data on the last read.
+    pub eof: bool,
+    /// The channels of the last decoded frame.
+    pub frame: Vec<Vec<i32>>,
+    /// Whether the last decoded frame is yet to be handed out.
+    pub frame_ready: bool,
+    /// Copies of the metadata blocks decoded so far.
+    pub metadata: Vec<FlacMetadata>,
+    /// The problems found in the stream so far.
+    pub stream_errors: Vec<FlacDecoderErrorStatus>,
+    /// The first error returned by the source, to be handed to the caller once control returns to Rust.
+    pub io_error: Option<io::Error>,
+    /// The payload of a panic caught in a callback, to be resumed once control returns to Rust.
+    pub panic: Option<Box<dyn Any + Send + 'static>>,
+}
+
+impl DecoderCallbackData {
+    /// Resume the unwind of a panic caught in one of the callbacks, if any.
+    pub fn resume_panic(&mut self) {
+        if let Some(payload) = self.panic.take() {
+            panic::resume_unwind(payload);
+        }
+    }
+
+    /// Call into the source, catching a panic, since unwinding into libFLAC is undefined behaviour.
+    ///
+    /// Returns `None` if the source errored, panicked, or if it had already panicked, in which case it isn't called again.
+    pub fn call_source<T, F: FnOnce(&mut DecoderCallbackData) -> io::Result<T>>(&mut self, f: F) -> Option<T> {
+        if self.panic.is_some() {
+            return None;
+        }
+
+        match panic::catch_unwind(AssertUnwindSafe(|| f(self))) {
+            Ok(Ok(ret)) => Some(ret),
+            Ok(Err(err)) => {
+                if self.io_error.is_none() {
+                    self.io_error = Some(err);
+                }
+                None
+            }
+            Err(payload) => {
+                self.panic = Some(payload);
+                None
+            }
+        }
+    }
+}
+
+impl fmt::Debug for DecoderCallbackData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DecoderCallbackData")
+            .field("read_seek", &self.read_seek.as_ref().map(|_| ".."))
+            .field("eof", &self.eof)
+            .field("frame", &self.frame.iter().map(|c| c.len()).collect::<Vec<_>>())
+            .field("frame_ready", &self.frame_ready)
+            .field("metadata", &self.metadata)
+            .field("stream_errors", &self.stream_errors)
+            .field("io_error", &self.io_error)
+            .field("panic", &self.panic.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+
+/// A source that can be both read from and seeked in, for use with [`ReadSeekWrapper`](struct.ReadSeekWrapper.html).
+///
+/// Automatically implemented for all `Read + Seek` types.
+pub trait ReadSeek: Read + Seek {}
+
+impl<T: Read + Seek + ?Sized> ReadSeek for T {}
+
+/// This wrapper is necessary due to [fat pointers](https://chat.stackoverflow.com/transcript/message/47940937#47940937),
+/// like [`WriteWrapper`](struct.WriteWrapper.html).
+///
+/// If the source panics, libFLAC is told the read failed, the source isn't called again,
+/// and the panic is resumed once control returns from the call that triggered it.
+///
+/// # Examples
+///
+/// ```
+/// # use flac_bound::{ReadSeekWrapper, FlacDecoder, FlacEncoder};
+/// # use std::fs::File;
+/// # FlacEncoder::new().unwrap().init_file(&"ЦшЦ-read.flac").unwrap().process_interleaved(&[0xA1, 0xF3], 1).unwrap();
+/// let mut inf = File::open("ЦшЦ-read.flac").unwrap();
+/// let mut inw = ReadSeekWrapper(&mut inf);
+/// let mut dec = FlacDecoder::new().unwrap().init_read_seek(&mut inw).unwrap();
+///
+/// assert_eq!(dec.read_frame().unwrap(), Some(&[vec![0xA1], vec![0xF3]][..]));
+/// ```
+pub struct ReadSeekWrapper<'src>(pub &'src mut dyn ReadSeek);
+
+
+pub unsafe extern "C" fn flac_decoder_read_seek_read_callback(_: *const FLAC__StreamDecoder, buffer: *mut u8, bytes: *mut usize, client_data: *mut c_void)
+                                                              -> FLAC__StreamDecoderReadStatus {
+    let data = &mut *(client_data as *mut DecoderCallbackData);
+
+    let buffer = slice::from_raw_parts_mut(buffer, *bytes);
+    match data.call_source(|data| {
+        let source = &mut (*data.read_seek.expect("no source").as_ptr()).0;
+        loop {
+            match source.read(buffer) {
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {}
+                ret => return ret,
+            }
+        }
+    }) {
+        Some(0) => {
+            *bytes = 0;
+            data.eof = true;
+            FLAC__StreamDecoderReadStatus_FLAC__STREAM_DECODER_READ_STATUS_END_OF_STREAM
+        }
+        Some(read) => {
+            *bytes = read;
+            FLAC__StreamDecoderReadStatus_FLAC__STREAM_DECODER_READ_STATUS_CONTINUE
+        }
+        None => {
+            *bytes = 0;
+            FLAC__StreamDecoderReadStatus_FLAC__STREAM_DECODER_READ_STATUS_ABORT
+        }
+    }
+}
+
+pub unsafe extern "C" fn flac_decoder_read_seek_seek_callback(_: *const FLAC__StreamDecoder, absolute_byte_offset: u64, client_data: *mut c_void)
+                                                              -> FLAC__StreamDecoderSeekStatus {
+    let data = &mut *(client_data as *mut DecoderCallbackData);
+
+    match data.call_source(|data| (*data.read_seek.expect("no source").as_ptr()).0.seek(SeekFrom::Start(absolute_byte_offset))) {
+        Some(_) => {
+            data.eof = false;
+            FLAC__StreamDecoderSeekStatus_FLAC__STREAM_DECODER_SEEK_STATUS_OK
+        }
+        None => FLAC__StreamDecoderSeekStatus_FLAC__STREAM_DECODER_SEEK_STATUS_ERROR,
+    }
+}
+
+pub unsafe extern "C" fn flac_decoder_read_seek_tell_callback(_: *const FLAC__StreamDecoder, absolute_byte_offset: *mut u64, client_data: *mut c_void)
+                                                              -> FLAC__StreamDecoderTellStatus {
+    let data = &mut *(client_data as *mut DecoderCallbackData);
+
+    match data.call_source(|data| (*data.read_seek.expect("no source").as_ptr()).0.stream_position()) {
+        Some(pos) => {
+            *absolute_byte_offset = pos;
+            FLAC__StreamDecoderTellStatus_FLAC__STREAM_DECODER_TELL_STATUS_OK
+        }
+        None => FLAC__StreamDecoderTellStatus_FLAC__STREAM_DECODER_TELL_STATUS_ERROR,
+    }
+}
+
+pub unsafe extern "C" fn flac_decoder_read_seek_length_callback(_: *const FLAC__StreamDecoder, stream_length: *mut u64, client_data: *mut c_void)
+                                                                -> FLAC__StreamDecoderLengthStatus {
+    let data = &mut *(client_data as *mut DecoderCallbackData);
+
+    match data.call_source(|data| {
+        let source = &mut (*data.read_seek.expect("no source").as_ptr()).0;
+        let pos = source.stream_position()?;
+        let len = source.seek(SeekFrom::End(0))?;
+        source.seek(SeekFrom::Start(pos))?;
+        Ok(len)
+    }) {
+        Some(len) => {
+            *stream_length = len;
+            FLAC__StreamDecoderLengthStatus_FLAC__STREAM_DECODER_LENGTH_STATUS_OK
+        }
+        None => FLAC__StreamDecoderLengthStatus_FLAC__STREAM_DECODER_LENGTH_STATUS_ERROR,
+    }
+}
+
+pub unsafe extern "C" fn flac_decoder_read_seek_eof_callback(_: *const FLAC__StreamDecoder, client_data: *mut c_void) -> FLAC__bool {
+    let data = &mut *(client_data as *mut DecoderCallbackData);
+    (data.eof || data.panic.is_some()) as FLAC__bool
+}
+
+
+pub unsafe extern "C" fn flac_decoder_write_callback(_: *const FLAC__StreamDecoder, frame: *const FLAC__Frame, buffer: *const *const i32,
+                                                     client_data: *mut c_void)
+                                                     -> FLAC__StreamDecoderWriteStatus {
+    let data = &mut *(client_data as *mut DecoderCallbackData);
+
+    let header = &(*frame).header;
+    let channels = slice::from_raw_parts(buffer, header.channels as usize);
+    data.frame.resize(channels.len(), Vec::new());
+    for (out, &channel) in data.frame.iter_mut().zip(channels) {
+        out.clear();
+        out.extend_from_slice(slice::from_raw_parts(channel, header.blocksize as usize));
+    }
+    data.frame_ready = true;
+
+    FLAC__StreamDecoderWriteStatus_FLAC__STREAM_DECODER_WRITE_STATUS_CONTINUE
+}
+
+pub unsafe extern "C" fn flac_decoder_metadata_callback(_: *const FLAC__StreamDecoder, metadata: *const FLAC__StreamMetadata, client_data: *mut c_void) {
+    let data = &mut *(client_data as *mut DecoderCallbackData);
+
+    let block = FLAC__metadata_object_clone(metadata);
+    if !block.is_null() {
+        data.metadata.push(FlacMetadata(block));
+    }
+}
+
+pub unsafe extern "C" fn flac_decoder_error_callback(_: *const FLAC__StreamDecoder, status: FLAC__StreamDecoderErrorStatus, client_data: *mut c_void) {
+    let data = &mut *(client_data as *mut DecoderCallbackData);
+
+    if let Ok(status) = FlacDecoderErrorStatus::try_from(status) {
+        data.stream_errors.push(status);
+    }
+}
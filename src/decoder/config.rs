@@ -0,0 +1,373 @@
+#[cfg(feature = "flac")]
+use flac_sys::{FLAC__StreamDecoderInitStatus, FLAC__bool, FLAC__stream_decoder_set_md5_checking, FLAC__stream_decoder_set_metadata_respond,
+               FLAC__stream_decoder_set_metadata_respond_application, FLAC__stream_decoder_set_metadata_respond_all,
+               FLAC__stream_decoder_set_metadata_ignore, FLAC__stream_decoder_set_metadata_ignore_application,
+               FLAC__stream_decoder_set_metadata_ignore_all, FLAC__stream_decoder_get_md5_checking, FLAC__stream_decoder_init_stream,
+               FLAC__stream_decoder_init_file, FLAC__stream_decoder_get_state, FLAC__stream_decoder_finish,
+               FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_OK, FLAC__StreamDecoderState_FLAC__STREAM_DECODER_UNINITIALIZED};
+
+#[cfg(feature = "libflac-nobuild")]
+use libflac_sys::{FLAC__StreamDecoderInitStatus, FLAC__bool, FLAC__stream_decoder_set_md5_checking, FLAC__stream_decoder_set_metadata_respond,
+                  FLAC__stream_decoder_set_metadata_respond_application, FLAC__stream_decoder_set_metadata_respond_all,
+                  FLAC__stream_decoder_set_metadata_ignore, FLAC__stream_decoder_set_metadata_ignore_application,
+                  FLAC__stream_decoder_set_metadata_ignore_all, FLAC__stream_decoder_get_md5_checking, FLAC__stream_decoder_init_stream,
+                  FLAC__stream_decoder_init_file, FLAC__stream_decoder_get_state, FLAC__stream_decoder_finish,
+                  FLAC__STREAM_DECODER_INIT_STATUS_OK as FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_OK,
+                  FLAC__STREAM_DECODER_UNINITIALIZED as FLAC__StreamDecoderState_FLAC__STREAM_DECODER_UNINITIALIZED};
+
+#[cfg(feature = "flac")]
+use flac_sys::{FLAC__stream_decoder_set_ogg_serial_number, FLAC__stream_decoder_init_ogg_stream, FLAC__stream_decoder_init_ogg_file};
+
+#[cfg(all(feature = "libflac-nobuild", not(feature = "libflac-noogg")))]
+use libflac_sys::{FLAC__stream_decoder_set_ogg_serial_number, FLAC__stream_decoder_init_ogg_stream, FLAC__stream_decoder_init_ogg_file};
+
+use util;
+use metadata::FlacMetadataType;
+use super::{StreamDecoderContainer, FlacDecoderInitError, DecoderCallbackData, ReadSeekWrapper, FlacDecoder, flac_decoder_read_seek_read_callback,
+            flac_decoder_read_seek_seek_callback, flac_decoder_read_seek_tell_callback, flac_decoder_read_seek_length_callback,
+            flac_decoder_read_seek_eof_callback, flac_decoder_write_callback, flac_decoder_metadata_callback, flac_decoder_error_callback};
+use std::marker::PhantomData;
+use std::convert::TryFrom;
+#[cfg(not(feature = "libflac-noogg"))]
+use std::os::raw::c_long;
+use std::os::raw::{c_char, c_void};
+use std::ptr::NonNull;
+use std::path::Path;
+use std::ptr;
+
+
+/// Wrapper around a FLAC decoder for configuring the input settings.
+///
+/// `FILE*` constructors unsupported, Read+Seek constructors go through [`ReadSeekWrapper`](struct.ReadSeekWrapper.html)
+/// due to <https://github.com/rust-lang/rfcs/issues/2035>
+///
+/// Like [`FlacEncoderConfig`](struct.FlacEncoderConfig.html), this is `Send`.
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct FlacDecoderConfig(pub(super) StreamDecoderContainer);
+
+impl FlacDecoderConfig {
+    /// Initialize the decoder instance to decode native FLAC streams.
+    ///
+    /// This flavor of initialization sets up the decoder to decode from a
+    /// native FLAC stream. I/O is performed from the specified source,
+    /// which is expected to be positioned at the start of the FLAC data, which need not be the start of the source.
+    ///
+    /// The source must be seekable so that the decoder can find the length of the stream
+    /// and [seek](struct.FlacDecoder.html) in it.
+    pub fn init_read_seek<'src>(self, source: &'src mut ReadSeekWrapper<'src>) -> Result<FlacDecoder<'src>, (FlacDecoderInitError, FlacDecoderConfig)> {
+        if !(self.0).1.is_empty() {
+            return Err((FlacDecoderInitError::SettingRejected, self));
+        }
+        let mut data = Box::new(DecoderCallbackData { read_seek: Some(NonNull::from(source).cast()), ..DecoderCallbackData::default() });
+        let result = unsafe {
+            FLAC__stream_decoder_init_stream((self.0).0,
+                                             Some(flac_decoder_read_seek_read_callback),
+                                             Some(flac_decoder_read_seek_seek_callback),
+                                             Some(flac_decoder_read_seek_tell_callback),
+                                             Some(flac_decoder_read_seek_length_callback),
+                                             Some(flac_decoder_read_seek_eof_callback),
+                                             Some(flac_decoder_write_callback),
+                                             Some(flac_decoder_metadata_callback),
+                                             Some(flac_decoder_error_callback),
+                                             &mut *data as *mut DecoderCallbackData as *mut c_void)
+        };
+        self.do_init(result, data)
+    }
+
+    /// Initialize the decoder instance to decode Ogg FLAC streams.
+    ///
+    /// This flavor of initialization sets up the decoder to decode from a
+    /// FLAC stream in an Ogg container. I/O is performed from the specified source,
+    /// as with [`init_read_seek()`](#method.init_read_seek).
+    ///
+    /// Only one logical stream is decoded: the one selected with
+    /// [`ogg_serial_number()`](#method.ogg_serial_number), or the first one found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use flac_bound::{ReadSeekWrapper, FlacDecoder, FlacEncoder, WriteWrapper};
+    /// # use std::io::Cursor;
+    /// // Chain two logical streams, as one would get from concatenating two .ogg files
+    /// let mut chained = vec![];
+    /// for (serial, sample) in [(1, 0x1A), (2, 0x2B)].iter() {
+    ///     let mut out = vec![];
+    ///     let mut outw = WriteWrapper(&mut out);
+    ///     let mut enc = FlacEncoder::new().unwrap().channels(1).ogg_serial_number(*serial).init_write_ogg(&mut outw).unwrap();
+    ///     enc.process(&[&[*sample]]).unwrap();
+    ///     enc.finish().unwrap();
+    ///     chained.extend_from_slice(&out);
+    /// }
+    ///
+    /// for (serial, sample) in [(None, 0x1A), (Some(1), 0x1A), (Some(2), 0x2B)].iter() {
+    ///     let mut source = Cursor::new(&chained);
+    ///     let mut sourcew = ReadSeekWrapper(&mut source);
+    ///     let mut conf = FlacDecoder::new().unwrap();
+    ///     if let Some(serial) = *serial {
+    ///         conf = conf.ogg_serial_number(serial);
+    ///     }
+    ///     let mut dec = conf.init_read_seek_ogg(&mut sourcew).unwrap();
+    ///     assert_eq!(dec.read_frame().unwrap(), Some(&[vec![*sample]][..]));
+    ///     assert_eq!(dec.read_frame().unwrap(), None);
+    /// }
+    /// ```
+    #[cfg(not(feature = "libflac-noogg"))]
+    pub fn init_read_seek_ogg<'src>(self, source: &'src mut ReadSeekWrapper<'src>) -> Result<FlacDecoder<'src>, (FlacDecoderInitError, FlacDecoderConfig)> {
+        if !(self.0).1.is_empty() {
+            return Err((FlacDecoderInitError::SettingRejected, self));
+        }
+        let mut data = Box::new(DecoderCallbackData { read_seek: Some(NonNull::from(source).cast()), ..DecoderCallbackData::default() });
+        let result = unsafe {
+            FLAC__stream_decoder_init_ogg_stream((self.0).0,
+                                                 Some(flac_decoder_read_seek_read_callback),
+                                                 Some(flac_decoder_read_seek_seek_callback),
+                                                 Some(flac_decoder_read_seek_tell_callback),
+                                                 Some(flac_decoder_read_seek_length_callback),
+                                                 Some(flac_decoder_read_seek_eof_callback),
+                                                 Some(flac_decoder_write_callback),
+                                                 Some(flac_decoder_metadata_callback),
+                                                 Some(flac_decoder_error_callback),
+                                                 &mut *data as *mut DecoderCallbackData as *mut c_void)
+        };
+        self.do_init(result, data)
+    }
+
+    /// Initialize the decoder instance to decode native FLAC files.
+    ///
+    /// This flavor of initialization sets up the decoder to decode from a
+    /// plain native FLAC file. If POSIX fopen() semantics are not sufficient,
+    /// (for example, with Unicode filenames on Windows), you must use
+    /// [`init_read_seek()`](#method.init_read_seek) and provide the source.
+    ///
+    /// The file will be opened with `fopen()`.
+    ///
+    /// The filename is passed as-is, except on Windows, where it must be valid Unicode;
+    /// a filename that can't be represented, or contains a NUL, fails with
+    /// [`InvalidFilename`](enum.FlacDecoderInitError.html#variant.InvalidFilename).
+    pub fn init_file<P: AsRef<Path>>(self, filename: &P) -> Result<FlacDecoder<'static>, (FlacDecoderInitError, FlacDecoderConfig)> {
+        let filename = match util::convert_path(filename.as_ref()) {
+            Some(filename) => filename,
+            None => return Err((FlacDecoderInitError::InvalidFilename, self)),
+        };
+        self.init_file_impl(filename.as_ptr())
+    }
+
+    /// Initialize the decoder instance to decode native FLAC from stdin.
+    ///
+    /// **Note**: stdin is not seekable, so seeking will fail.
+    pub fn init_stdin(self) -> Result<FlacDecoder<'static>, (FlacDecoderInitError, FlacDecoderConfig)> {
+        self.init_file_impl(ptr::null())
+    }
+
+    fn init_file_impl(self, filename: *const c_char) -> Result<FlacDecoder<'static>, (FlacDecoderInitError, FlacDecoderConfig)> {
+        if !(self.0).1.is_empty() {
+            return Err((FlacDecoderInitError::SettingRejected, self));
+        }
+        let mut data = Box::<DecoderCallbackData>::default();
+        let result = unsafe {
+            FLAC__stream_decoder_init_file((self.0).0,
+                                           filename,
+                                           Some(flac_decoder_write_callback),
+                                           Some(flac_decoder_metadata_callback),
+                                           Some(flac_decoder_error_callback),
+                                           &mut *data as *mut DecoderCallbackData as *mut c_void)
+        };
+        self.do_init(result, data)
+    }
+
+    /// Initialize the decoder instance to decode Ogg FLAC files.
+    ///
+    /// This flavor of initialization sets up the decoder to decode from a
+    /// plain Ogg FLAC file. If POSIX fopen() semantics are not sufficient,
+    /// (for example, with Unicode filenames on Windows), you must use
+    /// [`init_read_seek_ogg()`](#method.init_read_seek_ogg) and provide the source.
+    ///
+    /// The filename is handled as in [`init_file()`](#method.init_file).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use flac_bound::{FlacDecoder, FlacEncoder};
+    /// let mut enc = FlacEncoder::new().unwrap().channels(1).ogg_serial_number(0x1D).init_file_ogg(&"ЦшЦ-decoder.oga").unwrap();
+    /// enc.process(&[&[1, 2, 3]]).unwrap();
+    /// enc.finish().unwrap();
+    ///
+    /// let mut dec = FlacDecoder::new().unwrap().ogg_serial_number(0x1D).init_file_ogg(&"ЦшЦ-decoder.oga").unwrap();
+    /// assert_eq!(dec.read_frame().unwrap(), Some(&[vec![1, 2, 3]][..]));
+    /// # drop(dec);
+    /// # std::fs::remove_file("ЦшЦ-decoder.oga").unwrap();
+    /// ```
+    #[cfg(not(feature = "libflac-noogg"))]
+    pub fn init_file_ogg<P: AsRef<Path>>(self, filename: &P) -> Result<FlacDecoder<'static>, (FlacDecoderInitError, FlacDecoderConfig)> {
+        let filename = match util::convert_path(filename.as_ref()) {
+            Some(filename) => filename,
+            None => return Err((FlacDecoderInitError::InvalidFilename, self)),
+        };
+        self.init_file_ogg_impl(filename.as_ptr())
+    }
+
+    /// Initialize the decoder instance to decode Ogg FLAC from stdin.
+    ///
+    /// **Note**: stdin is not seekable, so seeking will fail.
+    #[cfg(not(feature = "libflac-noogg"))]
+    pub fn init_stdin_ogg(self) -> Result<FlacDecoder<'static>, (FlacDecoderInitError, FlacDecoderConfig)> {
+        self.init_file_ogg_impl(ptr::null())
+    }
+
+    #[cfg(not(feature = "libflac-noogg"))]
+    fn init_file_ogg_impl(self, filename: *const c_char) -> Result<FlacDecoder<'static>, (FlacDecoderInitError, FlacDecoderConfig)> {
+        if !(self.0).1.is_empty() {
+            return Err((FlacDecoderInitError::SettingRejected, self));
+        }
+        let mut data = Box::<DecoderCallbackData>::default();
+        let result = unsafe {
+            FLAC__stream_decoder_init_ogg_file((self.0).0,
+                                               filename,
+                                               Some(flac_decoder_write_callback),
+                                               Some(flac_decoder_metadata_callback),
+                                               Some(flac_decoder_error_callback),
+                                               &mut *data as *mut DecoderCallbackData as *mut c_void)
+        };
+        self.do_init(result, data)
+    }
+
+    /// Turn the result of a libFLAC init call into the decoder, or the error and the config to retry with.
+    ///
+    /// A source panicking while initialising fails it, and the panic is resumed once the decoder is reset.
+    fn do_init<'src>(self, init_result: FLAC__StreamDecoderInitStatus, mut data: Box<DecoderCallbackData>)
+                     -> Result<FlacDecoder<'src>, (FlacDecoderInitError, FlacDecoderConfig)> {
+        if init_result == FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_OK {
+            Ok(FlacDecoder(self.0, PhantomData, data))
+        } else {
+            // libFLAC refuses any further settings until the decoder is reset, which also resets them to their defaults
+            if unsafe { FLAC__stream_decoder_get_state((self.0).0) } != FLAC__StreamDecoderState_FLAC__STREAM_DECODER_UNINITIALIZED {
+                unsafe { FLAC__stream_decoder_finish((self.0).0) };
+            }
+            data.resume_panic();
+            Err((FlacDecoderInitError::try_from(init_result).unwrap(), self))
+        }
+    }
+
+    /// Get the settings libFLAC rejected, which will make initialisation fail with
+    /// [`SettingRejected`](enum.FlacDecoderInitError.html#variant.SettingRejected).
+    ///
+    /// libFLAC only rejects settings after initialisation, which a config never is, or if it runs out of memory.
+    pub fn rejected_settings(&self) -> &[&'static str] {
+        &(self.0).1
+    }
+
+    fn checked(mut self, setting: &'static str, result: FLAC__bool) -> FlacDecoderConfig {
+        (self.0).1.retain(|&s| s != setting);
+        if result == 0 {
+            (self.0).1.push(setting);
+        }
+        self
+    }
+}
+
+
+impl FlacDecoderConfig {
+    /// Set the serial number of the logical stream to decode from an Ogg container.
+    ///
+    /// **Note**:<br />
+    /// This does not need to be set for native FLAC decoding.
+    ///
+    /// Ogg serial numbers are 32-bit, so this takes an `i32` rather than libFLAC's `c_long`.
+    ///
+    /// **Default**: By default, the decoder uses the serial number of the first page it finds.
+    #[cfg(not(feature = "libflac-noogg"))]
+    pub fn ogg_serial_number(self, serial_number: i32) -> FlacDecoderConfig {
+        let result = unsafe { FLAC__stream_decoder_set_ogg_serial_number((self.0).0, c_long::from(serial_number)) };
+        self.checked("ogg_serial_number", result)
+    }
+
+    /// Set the "MD5 signature checking" flag.
+    ///
+    /// If `true`, the decoder will compute the MD5 signature of the unencoded audio data while decoding
+    /// and compare it to the signature from the STREAMINFO block, if it exists,
+    /// during [`FlacDecoder::finish()`](struct.FlacDecoder.html#method.finish).
+    ///
+    /// MD5 signature checking will be turned off if there is no signature in the STREAMINFO block or when a seek is attempted.
+    ///
+    /// **Default**: `false`
+    pub fn md5_checking(self, value: bool) -> FlacDecoderConfig {
+        let result = unsafe { FLAC__stream_decoder_set_md5_checking((self.0).0, value as FLAC__bool) };
+        self.checked("md5_checking", result)
+    }
+
+    /// Get the "MD5 signature checking" flag.
+    ///
+    /// See [`md5_checking()`](#method.md5_checking).
+    pub fn get_md5_checking(&self) -> bool {
+        unsafe { FLAC__stream_decoder_get_md5_checking((self.0).0) != 0 }
+    }
+
+    /// Direct the decoder to collect the metadata blocks of the given type.
+    ///
+    /// Blocks are available through [`FlacDecoder::metadata()`](struct.FlacDecoder.html#method.metadata) once decoded.
+    ///
+    /// **Default**: By default, only the STREAMINFO block is collected.
+    pub fn metadata_respond(self, tp: FlacMetadataType) -> FlacDecoderConfig {
+        let result = unsafe { FLAC__stream_decoder_set_metadata_respond((self.0).0, tp.into()) };
+        self.checked("metadata_respond", result)
+    }
+
+    /// Direct the decoder to collect the APPLICATION metadata blocks with the given
+    /// [registered](https://xiph.org/flac/id.html) application ID.
+    ///
+    /// **Default**: By default, only the STREAMINFO block is collected.
+    pub fn metadata_respond_application(self, id: [u8; 4]) -> FlacDecoderConfig {
+        let result = unsafe { FLAC__stream_decoder_set_metadata_respond_application((self.0).0, id.as_ptr()) };
+        self.checked("metadata_respond_application", result)
+    }
+
+    /// Direct the decoder to collect all metadata blocks of any type.
+    ///
+    /// **Default**: By default, only the STREAMINFO block is collected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use flac_bound::{FlacMetadataType, FlacDecoder, FlacEncoder, FlacMetadata};
+    /// # FlacEncoder::new().unwrap().metadata_block(FlacMetadata::padding(16).unwrap()).init_file(&"ЦшЦ-respond.flac").unwrap();
+    /// let mut dec = FlacDecoder::new().unwrap()
+    ///     .metadata_respond_all()
+    ///     .metadata_ignore(FlacMetadataType::StreamInfo)
+    ///     .init_file(&"ЦшЦ-respond.flac")
+    ///     .unwrap();
+    /// dec.process_until_end_of_metadata().unwrap();
+    ///
+    /// let types: Vec<_> = dec.metadata().iter().map(|b| b.metadata_type()).collect();
+    /// assert_eq!(types, [FlacMetadataType::VorbisComment, FlacMetadataType::Padding]);
+    /// ```
+    pub fn metadata_respond_all(self) -> FlacDecoderConfig {
+        let result = unsafe { FLAC__stream_decoder_set_metadata_respond_all((self.0).0) };
+        self.checked("metadata_respond_all", result)
+    }
+
+    /// Direct the decoder to skip the metadata blocks of the given type.
+    ///
+    /// **Default**: By default, only the STREAMINFO block is collected.
+    pub fn metadata_ignore(self, tp: FlacMetadataType) -> FlacDecoderConfig {
+        let result = unsafe { FLAC__stream_decoder_set_metadata_ignore((self.0).0, tp.into()) };
+        self.checked("metadata_ignore", result)
+    }
+
+    /// Direct the decoder to skip the APPLICATION metadata blocks with the given
+    /// [registered](https://xiph.org/flac/id.html) application ID.
+    ///
+    /// **Default**: By default, only the STREAMINFO block is collected.
+    pub fn metadata_ignore_application(self, id: [u8; 4]) -> FlacDecoderConfig {
+        let result = unsafe { FLAC__stream_decoder_set_metadata_ignore_application((self.0).0, id.as_ptr()) };
+        self.checked("metadata_ignore_application", result)
+    }
+
+    /// Direct the decoder to skip all metadata blocks, including STREAMINFO.
+    ///
+    /// **Default**: By default, only the STREAMINFO block is collected.
+    pub fn metadata_ignore_all(self) -> FlacDecoderConfig {
+        let result = unsafe { FLAC__stream_decoder_set_metadata_ignore_all((self.0).0) };
+        self.checked("metadata_ignore_all", result)
+    }
+}
@@ -0,0 +1,371 @@
+#[cfg(feature = "flac")]
+use flac_sys::{FLAC__stream_decoder_new, FLAC__stream_decoder_get_state, FLAC__stream_decoder_get_resolved_state_string, FLAC__stream_decoder_finish,
+               FLAC__stream_decoder_process_single, FLAC__stream_decoder_process_until_end_of_metadata, FLAC__stream_decoder_get_md5_checking,
+               FLAC__stream_decoder_get_total_samples, FLAC__stream_decoder_get_channels, FLAC__stream_decoder_get_bits_per_sample,
+               FLAC__stream_decoder_get_sample_rate, FLAC__stream_decoder_get_blocksize, FLAC__stream_decoder_seek_absolute, FLAC__stream_decoder_flush};
+
+#[cfg(feature = "libflac-nobuild")]
+use libflac_sys::{FLAC__stream_decoder_new, FLAC__stream_decoder_get_state, FLAC__stream_decoder_get_resolved_state_string, FLAC__stream_decoder_finish,
+                  FLAC__stream_decoder_process_single, FLAC__stream_decoder_process_until_end_of_metadata, FLAC__stream_decoder_get_md5_checking,
+                  FLAC__stream_decoder_get_total_samples, FLAC__stream_decoder_get_channels, FLAC__stream_decoder_get_bits_per_sample,
+                  FLAC__stream_decoder_get_sample_rate, FLAC__stream_decoder_get_blocksize, FLAC__stream_decoder_seek_absolute, FLAC__stream_decoder_flush};
+
+use super::{StreamDecoderContainer, DecoderCallbackData, FlacDecoderErrorStatus, FlacDecoderConfig, FlacDecoderError, FlacDecoderState};
+use metadata::FlacMetadata;
+use std::marker::PhantomData;
+use std::convert::TryFrom;
+use std::time::Duration;
+use std::ffi::CStr;
+use std::{mem, ptr};
+
+
+/// The [stream decoder](https://xiph.org/flac/api/group__flac__stream__decoder.html) can decode native FLAC,
+/// and Ogg FLAC (unless the `"libflac-noogg"` feature is on) streams and files.
+///
+/// The basic usage of this decoder is as follows:
+///   * The program creates an instance of a decoder using
+///     [`FlacDecoder::new()`](#method.new).
+///   * The program overrides the default settings using functions in
+///     [`FlacDecoderConfig`](struct.FlacDecoderConfig.html), like
+///     [`md5_checking()`](struct.FlacDecoderConfig.html#method.md5_checking), the `metadata_respond*()`/`metadata_ignore*()` filters,
+///     and [`ogg_serial_number()`](struct.FlacDecoderConfig.html#method.ogg_serial_number) (if decoding Ogg FLAC).
+///   * The program initializes the instance to validate the settings and
+///     prepare for decoding using
+///       * [`FlacDecoderConfig::init_read_seek()`](struct.FlacDecoderConfig.html#method.init_read_seek), or
+///         [`FlacDecoderConfig::init_file()`](struct.FlacDecoderConfig.html#method.init_file), or
+///         [`FlacDecoderConfig::init_stdin()`](struct.FlacDecoderConfig.html#method.init_stdin) for native FLAC
+///       * [`FlacDecoderConfig::init_read_seek_ogg()`](struct.FlacDecoderConfig.html#method.init_read_seek_ogg), or
+///         [`FlacDecoderConfig::init_file_ogg()`](struct.FlacDecoderConfig.html#method.init_file_ogg), or
+///         [`FlacDecoderConfig::init_stdin_ogg()`](struct.FlacDecoderConfig.html#method.init_stdin_ogg) for Ogg FLAC
+///   * The program calls [`FlacDecoder::read_frame()`](#method.read_frame) to decode the audio frame by frame,
+///     into one buffer per channel, until it returns `Ok(None)` at the end of the stream.
+///     [`FlacDecoder::process_until_end_of_metadata()`](#method.process_until_end_of_metadata) can be used to
+///     read only the metadata, which is then available through [`FlacDecoder::metadata()`](#method.metadata).
+///   * The program finishes the decoding with [`FlacDecoder::finish()`](#method.finish),
+///     which checks the MD5 signature, if enabled, and resets the decoder to the uninitialized state.
+///     Note: the stream is `finish()`ed when it's dropped, and any potential error is ignored.
+///
+/// Problems found in the stream, which the decoder recovers from by skipping to the next frame,
+/// are collected in [`FlacDecoder::stream_errors()`](#method.stream_errors).
+///
+/// The decoder isn't `Send`, since it may be reading from a borrowed source,
+/// but the [`FlacDecoderConfig`](struct.FlacDecoderConfig.html) is.
+///
+/// **Note**:<br />
+/// [`FlacDecoder::finish()`](#method.finish) resets all settings to the constructor defaults.
+///
+/// # Examples
+///
+/// ```
+/// # use flac_bound::{FlacDecoder, FlacEncoder};
+/// let mut enc = FlacEncoder::new().unwrap().channels(1).init_file(&"ЦшЦ-decoder.flac").unwrap();
+/// enc.process(&[&[0, 1, 2, 3, 2, 1, 0, -1]]).unwrap();
+/// enc.finish().unwrap();
+///
+/// let mut dec = FlacDecoder::new().unwrap().md5_checking(true).init_file(&"ЦшЦ-decoder.flac").unwrap();
+/// let mut samples = vec![];
+/// while let Some(frame) = dec.read_frame().unwrap() {
+///     samples.extend_from_slice(&frame[0]);
+/// }
+/// assert_eq!(samples, [0, 1, 2, 3, 2, 1, 0, -1]);
+/// assert_eq!((dec.get_channels(), dec.get_bits_per_sample(), dec.get_total_samples()), (1, 16, 8));
+/// dec.finish().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct FlacDecoder<'src>(pub(super) StreamDecoderContainer, pub(super) PhantomData<&'src mut ()>, pub(super) Box<DecoderCallbackData>);
+
+impl<'src> FlacDecoder<'src> {
+    /// Create a new stream decoder, in a configuration wrapper, or `None` if one couldn't be allocated.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> Option<FlacDecoderConfig> {
+        let dec = unsafe { FLAC__stream_decoder_new() };
+        if !dec.is_null() {
+            Some(FlacDecoderConfig(StreamDecoderContainer(dec, Vec::new())))
+        } else {
+            None
+        }
+    }
+
+    /// Get the current decoder state.
+    pub fn state(&self) -> FlacDecoderState {
+        FlacDecoderState::try_from(unsafe { FLAC__stream_decoder_get_state((self.0).0) }).unwrap()
+    }
+
+    /// Get the current decoder state as a human-readable string.
+    pub fn resolved_state_string(&self) -> &'static str {
+        unsafe { CStr::from_ptr(FLAC__stream_decoder_get_resolved_state_string((self.0).0)) }.to_str().unwrap_or("")
+    }
+
+    /// Get the "MD5 signature checking" flag.
+    ///
+    /// This is the value of the setting, not whether or not the decoder is currently checking the MD5,
+    /// which it stops doing if there's no signature in the STREAMINFO block, or after a seek.
+    pub fn get_md5_checking(&self) -> bool {
+        unsafe { FLAC__stream_decoder_get_md5_checking((self.0).0) != 0 }
+    }
+
+    /// Get the total number of samples in the stream being decoded, from the STREAMINFO block; `0` means unknown.
+    ///
+    /// Only valid once the STREAMINFO block has been decoded.
+    pub fn get_total_samples(&self) -> u64 {
+        unsafe { FLAC__stream_decoder_get_total_samples((self.0).0) }
+    }
+
+    /// Get the number of channels in the last decoded frame, or `0` before any.
+    pub fn get_channels(&self) -> u32 {
+        unsafe { FLAC__stream_decoder_get_channels((self.0).0) }
+    }
+
+    /// Get the resolution of the last decoded frame, or `0` before any.
+    pub fn get_bits_per_sample(&self) -> u32 {
+        unsafe { FLAC__stream_decoder_get_bits_per_sample((self.0).0) }
+    }
+
+    /// Get the sample rate of the last decoded frame, or `0` before any.
+    pub fn get_sample_rate(&self) -> u32 {
+        unsafe { FLAC__stream_decoder_get_sample_rate((self.0).0) }
+    }
+
+    /// Get the block size of the last decoded frame, or `0` before any.
+    pub fn get_blocksize(&self) -> u32 {
+        unsafe { FLAC__stream_decoder_get_blocksize((self.0).0) }
+    }
+
+    /// Decode the next audio frame, returning one buffer per channel, each holding the same amount of samples,
+    /// or `None` at the end of the stream.
+    ///
+    /// Each sample is a signed integer, right-justified to the resolution reported by
+    /// [`get_bits_per_sample()`](#method.get_bits_per_sample).
+    ///
+    /// Any metadata blocks before the frame are decoded along the way.
+    ///
+    /// Once the decoder is in an error state, like [`Aborted`](enum.FlacDecoderState.html#variant.Aborted)
+    /// after the source failed, this keeps returning an error.
+    ///
+    /// If the source panicked, the panic is resumed here.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use flac_bound::{FlacDecoderState, ReadSeekWrapper, FlacDecoder, FlacEncoder, WriteWrapper};
+    /// # use std::io::{self, Cursor, Read, Seek, SeekFrom};
+    /// struct Fragile(Cursor<Vec<u8>>, usize);
+    /// impl Read for Fragile {
+    ///     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    ///         if self.1 == 0 {
+    ///             return Err(io::Error::new(io::ErrorKind::Other, "worn out"));
+    ///         }
+    ///         self.1 -= 1;
+    ///         let len = buf.len().min(16);
+    ///         self.0.read(&mut buf[..len])
+    ///     }
+    /// }
+    /// impl Seek for Fragile {
+    ///     fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+    ///         self.0.seek(pos)
+    ///     }
+    /// }
+    ///
+    /// let mut out = vec![];
+    /// let mut outw = WriteWrapper(&mut out);
+    /// let mut enc = FlacEncoder::new().unwrap().channels(1).init_write(&mut outw).unwrap();
+    /// enc.process(&[&(0..20000).map(|i| (i * 7919) % 30000).collect::<Vec<_>>()]).unwrap();
+    /// enc.finish().unwrap();
+    ///
+    /// let mut source = Fragile(Cursor::new(out), 3);
+    /// let mut sourcew = ReadSeekWrapper(&mut source);
+    /// let mut dec = FlacDecoder::new().unwrap().init_read_seek(&mut sourcew).unwrap();
+    /// let err = dec.read_frame().unwrap_err();
+    /// assert_eq!(err.state, FlacDecoderState::Aborted);
+    /// assert_eq!(err.io_error.unwrap().to_string(), "worn out");
+    ///
+    /// let err = dec.read_frame().unwrap_err();
+    /// assert_eq!(err.state, FlacDecoderState::Aborted);
+    /// assert!(err.io_error.is_none());
+    /// ```
+    pub fn read_frame(&mut self) -> Result<Option<&[Vec<i32>]>, FlacDecoderError> {
+        while !self.2.frame_ready {
+            match self.state() {
+                FlacDecoderState::SearchForMetadata | FlacDecoderState::ReadMetadata | FlacDecoderState::SearchForFrameSync | FlacDecoderState::ReadFrame => {}
+                FlacDecoderState::EndOfStream => return Ok(None),
+                // libFLAC reports success without decoding anything in these
+                _ => return Err(self.error()),
+            }
+
+            let result = unsafe { FLAC__stream_decoder_process_single((self.0).0) };
+            self.2.resume_panic();
+            if result == 0 {
+                return Err(self.error());
+            }
+        }
+        self.2.frame_ready = false;
+        Ok(Some(&self.2.frame))
+    }
+
+    /// Seek to the given sample, so that the next [`read_frame()`](#method.read_frame) returns samples starting exactly there.
+    ///
+    /// libFLAC uses the SEEKTABLE block to narrow down the search, if there is one, and bisects the stream otherwise,
+    /// so the source needs to be seekable and its length known for this to work.
+    ///
+    /// The decoder stops checking the MD5 signature after a seek.
+    ///
+    /// The metadata is decoded first, if it hasn't been already, to get the length of the stream.
+    /// Seeking to or past the end of it fails with [`seek_past_end`](struct.FlacDecoderError.html#structfield.seek_past_end) set,
+    /// without touching the decoder.
+    ///
+    /// Otherwise, the error's state is the one libFLAC left the decoder in, e.g.
+    /// [`SeekError`](enum.FlacDecoderState.html#variant.SeekError) if the source failed to seek;
+    /// the decoder is then flushed so decoding can continue, from an unspecified position.
+    /// If the decoder can't seek at all, e.g. when decoding from stdin, the state is unchanged.
+    ///
+    /// If the source panicked, the panic is resumed here.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use flac_bound::{FlacDecoder, FlacEncoder};
+    /// let samples: Vec<i32> = (0..20000).map(|i| i % 1000).collect();
+    /// let mut enc = FlacEncoder::new().unwrap().channels(1).init_file(&"ЦшЦ-seek.flac").unwrap();
+    /// enc.process(&[&samples]).unwrap();
+    /// enc.finish().unwrap();
+    ///
+    /// let mut dec = FlacDecoder::new().unwrap().init_file(&"ЦшЦ-seek.flac").unwrap();
+    /// assert_eq!(dec.seek_to_sample(1 << 40).unwrap_err().to_string(), "sample 1099511627776 is past the end of the stream");
+    /// dec.seek_to_sample(12345).unwrap();
+    /// assert_eq!(dec.read_frame().unwrap().unwrap()[0][..3], samples[12345..12348]);
+    ///
+    /// dec.seek_to_sample(3).unwrap();
+    /// assert_eq!(dec.read_frame().unwrap().unwrap()[0][0], 3);
+    ///
+    /// assert_eq!(dec.seek_to_sample(20000).unwrap_err().seek_past_end, Some(20000));
+    /// dec.seek_to_sample(19999).unwrap();
+    /// assert_eq!(dec.read_frame().unwrap().unwrap()[0], [999]);
+    /// assert_eq!(dec.read_frame().unwrap(), None);
+    /// ```
+    pub fn seek_to_sample(&mut self, sample: u64) -> Result<(), FlacDecoderError> {
+        match self.state() {
+            FlacDecoderState::SearchForMetadata | FlacDecoderState::ReadMetadata => self.process_until_end_of_metadata()?,
+            _ => {}
+        }
+        let total_samples = self.get_total_samples();
+        if total_samples != 0 && sample >= total_samples {
+            let mut err = self.error();
+            err.seek_past_end = Some(sample);
+            return Err(err);
+        }
+
+        self.2.frame_ready = false;
+        let result = unsafe { FLAC__stream_decoder_seek_absolute((self.0).0, sample) };
+        self.2.resume_panic();
+        if result != 0 {
+            Ok(())
+        } else {
+            let err = self.error();
+            if err.state == FlacDecoderState::SeekError {
+                unsafe { FLAC__stream_decoder_flush((self.0).0) };
+            }
+            Err(err)
+        }
+    }
+
+    /// Seek to the sample at the given time from the start of the stream, rounding down,
+    /// as with [`seek_to_sample()`](#method.seek_to_sample).
+    ///
+    /// libFLAC only learns the sample rate from frame headers, so the first frame is decoded beforehand if none has been yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use flac_bound::{FlacDecoder, FlacEncoder};
+    /// # use std::time::Duration;
+    /// let samples: Vec<i32> = (0..8000).collect();
+    /// let mut enc = FlacEncoder::new().unwrap().channels(1).sample_rate(8000).init_file(&"ЦшЦ-seek-time.flac").unwrap();
+    /// enc.process(&[&samples]).unwrap();
+    /// enc.finish().unwrap();
+    ///
+    /// let mut dec = FlacDecoder::new().unwrap().init_file(&"ЦшЦ-seek-time.flac").unwrap();
+    /// dec.seek_to_time(Duration::from_millis(625)).unwrap();
+    /// assert_eq!(dec.read_frame().unwrap().unwrap()[0][0], 5000);
+    /// ```
+    pub fn seek_to_time(&mut self, time: Duration) -> Result<(), FlacDecoderError> {
+        if self.get_sample_rate() == 0 {
+            self.read_frame()?;
+        }
+        let sample = time.as_nanos() * self.get_sample_rate() as u128 / 1_000_000_000;
+        self.seek_to_sample(sample.min(u64::MAX as u128) as u64)
+    }
+
+    /// Decode until the end of the metadata, i.e. up to the first audio frame, or the end of the stream.
+    ///
+    /// If the source panicked, the panic is resumed here.
+    pub fn process_until_end_of_metadata(&mut self) -> Result<(), FlacDecoderError> {
+        let result = unsafe { FLAC__stream_decoder_process_until_end_of_metadata((self.0).0) };
+        self.2.resume_panic();
+        if result != 0 {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    /// Get the metadata blocks decoded so far, in stream order, as filtered by the
+    /// [`FlacDecoderConfig::metadata_respond*()`](struct.FlacDecoderConfig.html#method.metadata_respond) and
+    /// [`metadata_ignore*()`](struct.FlacDecoderConfig.html#method.metadata_ignore) settings.
+    pub fn metadata(&self) -> &[FlacMetadata] {
+        &self.2.metadata
+    }
+
+    /// Get the problems found in the stream so far, which the decoder recovered from.
+    pub fn stream_errors(&self) -> &[FlacDecoderErrorStatus] {
+        &self.2.stream_errors
+    }
+
+    /// Finish the decoding process.
+    ///
+    /// Flushes the decoding buffer, releases resources, resets the decoder
+    /// settings to their defaults, and returns the decoder state to
+    /// uninitialized.
+    ///
+    /// This is also called by `drop()`.
+    ///
+    /// Returns `Err()` if MD5 checking is on AND a STREAMINFO block was available
+    /// AND the MD5 signature in the STREAMINFO block was non-zero AND the
+    /// signature does not match the one computed by the decoder; else the config wrapper.
+    /// The config wrapper is returned alongside the error, too, reset just the same.
+    pub fn finish(mut self) -> Result<FlacDecoderConfig, (FlacDecoderError, FlacDecoderConfig)> {
+        let state = self.state();
+        let result = unsafe { FLAC__stream_decoder_finish((self.0).0) };
+        self.2.resume_panic();
+
+        let conf = FlacDecoderConfig(mem::replace(&mut self.0, StreamDecoderContainer(ptr::null_mut(), Vec::new())));
+        if result != 0 {
+            Ok(conf)
+        } else {
+            let err = FlacDecoderError {
+                state,
+                io_error: self.2.io_error.take(),
+                md5_mismatch: true,
+                seek_past_end: None,
+            };
+            Err((err, conf))
+        }
+    }
+
+    /// Collect what went wrong after a failed call.
+    fn error(&mut self) -> FlacDecoderError {
+        FlacDecoderError {
+            state: self.state(),
+            io_error: self.2.io_error.take(),
+            md5_mismatch: false,
+            seek_past_end: None,
+        }
+    }
+}
+
+impl<'src> Drop for FlacDecoder<'src> {
+    fn drop(&mut self) {
+        if !(self.0).0.is_null() {
+            unsafe { FLAC__stream_decoder_finish((self.0).0) };
+        }
+    }
+}
@@ -0,0 +1,233 @@
+#[cfg(feature = "flac")]
+use flac_sys::{FLAC__StreamDecoderInitStatus, FLAC__StreamDecoderInitStatusString, FLAC__StreamDecoderErrorStatus, FLAC__StreamDecoderErrorStatusString,
+               FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_UNSUPPORTED_CONTAINER,
+               FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_INVALID_CALLBACKS,
+               FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_MEMORY_ALLOCATION_ERROR,
+               FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_ERROR_OPENING_FILE,
+               FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_ALREADY_INITIALIZED,
+               FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_LOST_SYNC,
+               FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_BAD_HEADER,
+               FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_FRAME_CRC_MISMATCH,
+               FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_UNPARSEABLE_STREAM};
+
+#[cfg(feature = "libflac-nobuild")]
+use libflac_sys::{FLAC__StreamDecoderInitStatus, FLAC__StreamDecoderInitStatusString, FLAC__StreamDecoderErrorStatus, FLAC__StreamDecoderErrorStatusString,
+                  FLAC__STREAM_DECODER_INIT_STATUS_UNSUPPORTED_CONTAINER as FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_UNSUPPORTED_CONTAINER,
+                  FLAC__STREAM_DECODER_INIT_STATUS_INVALID_CALLBACKS as FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_INVALID_CALLBACKS,
+                  FLAC__STREAM_DECODER_INIT_STATUS_MEMORY_ALLOCATION_ERROR as FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_MEMORY_ALLOCATION_ERROR,
+                  FLAC__STREAM_DECODER_INIT_STATUS_ERROR_OPENING_FILE as FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_ERROR_OPENING_FILE,
+                  FLAC__STREAM_DECODER_INIT_STATUS_ALREADY_INITIALIZED as FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_ALREADY_INITIALIZED,
+                  FLAC__STREAM_DECODER_ERROR_STATUS_LOST_SYNC as FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_LOST_SYNC,
+                  FLAC__STREAM_DECODER_ERROR_STATUS_BAD_HEADER as FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_BAD_HEADER,
+                  FLAC__STREAM_DECODER_ERROR_STATUS_FRAME_CRC_MISMATCH as FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_FRAME_CRC_MISMATCH,
+                  FLAC__STREAM_DECODER_ERROR_STATUS_UNPARSEABLE_STREAM as FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_UNPARSEABLE_STREAM,
+                  FLAC__STREAM_DECODER_ERROR_STATUS_BAD_METADATA as FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_BAD_METADATA,
+                  FLAC__STREAM_DECODER_ERROR_STATUS_OUT_OF_BOUNDS as FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_OUT_OF_BOUNDS,
+                  FLAC__STREAM_DECODER_ERROR_STATUS_MISSING_FRAME as FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_MISSING_FRAME};
+
+use super::FlacDecoderState;
+use std::os::raw::c_char;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::ffi::CStr;
+use std::{fmt, ptr, io};
+
+
+/// Possible erroneous return values for the [`FlacDecoderConfig::init_*()`](struct.FlacDecoderConfig.html#method.init_read_seek) functions.
+///
+/// These are returned alongside the config, with its settings intact, so it can be corrected and initialised again.
+///
+/// # Examples
+///
+/// ```
+/// # use flac_bound::{FlacDecoderInitError, FlacDecoder};
+/// let (err, _conf) = FlacDecoder::new().unwrap().init_file(&"/nonexistent/ЦшЦ-decoder-init.flac").unwrap_err();
+/// assert_eq!(err, FlacDecoderInitError::ErrorOpeningFile);
+/// ```
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum FlacDecoderInitError {
+    /// The library was not compiled with support for the given container format.
+    UnsupportedContainer = FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_UNSUPPORTED_CONTAINER,
+
+    /// A required callback was not supplied.
+    InvalidCallbacks = FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_INVALID_CALLBACKS,
+
+    /// An error occurred allocating memory.
+    MemoryAllocationError = FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_MEMORY_ALLOCATION_ERROR,
+
+    /// fopen() failed in [`FlacDecoderConfig::init_file()`](struct.FlacDecoderConfig.html#method.init_file).
+    ErrorOpeningFile = FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_ERROR_OPENING_FILE,
+
+    /// [`FlacDecoderConfig::init_*()`](struct.FlacDecoderConfig.html#method.init_read_seek) was called when the decoder was already
+    /// initialized, usually because FLAC__stream_decoder_finish() was not called.
+    AlreadyInitialized = FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_ALREADY_INITIALIZED,
+
+    /// libFLAC rejected one of the settings, listed in
+    /// [`FlacDecoderConfig::rejected_settings()`](struct.FlacDecoderConfig.html#method.rejected_settings),
+    /// so libFLAC wasn't asked to initialise the decoder.
+    ///
+    /// This isn't a libFLAC status, and converts to an out-of-range `FLAC__StreamDecoderInitStatus`.
+    SettingRejected = 0xFFFF_FFFF,
+
+    /// The filename couldn't be handed to libFLAC, since it contains a NUL,
+    /// or, on Windows, where libFLAC expects UTF-8, isn't valid Unicode,
+    /// so libFLAC wasn't asked to initialise the decoder.
+    ///
+    /// This isn't a libFLAC status, and converts to an out-of-range `FLAC__StreamDecoderInitStatus`.
+    InvalidFilename = 0xFFFF_FFFE,
+}
+
+impl From<FlacDecoderInitError> for FLAC__StreamDecoderInitStatus {
+    fn from(val: FlacDecoderInitError) -> FLAC__StreamDecoderInitStatus {
+        val as FLAC__StreamDecoderInitStatus
+    }
+}
+
+impl TryFrom<FLAC__StreamDecoderInitStatus> for FlacDecoderInitError {
+    type Error = ();
+
+    #[allow(non_upper_case_globals)]
+    fn try_from(raw: FLAC__StreamDecoderInitStatus) -> Result<FlacDecoderInitError, ()> {
+        Ok(match raw {
+            FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_UNSUPPORTED_CONTAINER => FlacDecoderInitError::UnsupportedContainer,
+            FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_INVALID_CALLBACKS => FlacDecoderInitError::InvalidCallbacks,
+            FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_MEMORY_ALLOCATION_ERROR => FlacDecoderInitError::MemoryAllocationError,
+            FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_ERROR_OPENING_FILE => FlacDecoderInitError::ErrorOpeningFile,
+            FLAC__StreamDecoderInitStatus_FLAC__STREAM_DECODER_INIT_STATUS_ALREADY_INITIALIZED => FlacDecoderInitError::AlreadyInitialized,
+            _ => return Err(()),
+        })
+    }
+}
+
+/// Uses libFLAC's `FLAC__StreamDecoderInitStatusString` for libFLAC's statuses, and plain descriptions for the rest.
+impl fmt::Display for FlacDecoderInitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FlacDecoderInitError::SettingRejected => return f.write_str("a setting was rejected: see rejected_settings()"),
+            FlacDecoderInitError::InvalidFilename => return f.write_str("the filename can't be passed to libFLAC"),
+            _ => {}
+        }
+        let name = unsafe { CStr::from_ptr(*(ptr::addr_of!(FLAC__StreamDecoderInitStatusString) as *const *const c_char).add(*self as usize)) };
+        f.write_str(&name.to_string_lossy())
+    }
+}
+
+impl Error for FlacDecoderInitError {}
+
+
+/// Problems found in the stream while decoding, which the decoder recovers from.
+///
+/// These are collected by the decoder, see [`FlacDecoder::stream_errors()`](struct.FlacDecoder.html#method.stream_errors).
+///
+/// Statuses added in later libFLAC versions than the backend's headers describe are dropped.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum FlacDecoderErrorStatus {
+    /// An error in the stream caused the decoder to lose synchronization.
+    LostSync = FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_LOST_SYNC,
+
+    /// The decoder encountered a corrupted frame header.
+    BadHeader = FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_BAD_HEADER,
+
+    /// The frame's data did not match the CRC in the footer.
+    FrameCrcMismatch = FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_FRAME_CRC_MISMATCH,
+
+    /// The decoder encountered reserved fields in use in the stream.
+    UnparseableStream = FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_UNPARSEABLE_STREAM,
+
+    /// The decoder encountered a corrupted metadata block.
+    #[cfg(feature = "libflac-nobuild")]
+    BadMetadata = FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_BAD_METADATA,
+
+    /// The decoder encountered an otherwise valid frame in which the decoded samples exceeded the range offered by the stated bit depth.
+    #[cfg(feature = "libflac-nobuild")]
+    OutOfBounds = FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_OUT_OF_BOUNDS,
+
+    /// Two adjacent frames had frame numbers increasing by more than 1 or sample numbers increasing by more than the blocksize,
+    /// indicating that one or more frame/frames was missing between them.
+    #[cfg(feature = "libflac-nobuild")]
+    MissingFrame = FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_MISSING_FRAME,
+}
+
+impl From<FlacDecoderErrorStatus> for FLAC__StreamDecoderErrorStatus {
+    fn from(val: FlacDecoderErrorStatus) -> FLAC__StreamDecoderErrorStatus {
+        val as FLAC__StreamDecoderErrorStatus
+    }
+}
+
+impl TryFrom<FLAC__StreamDecoderErrorStatus> for FlacDecoderErrorStatus {
+    type Error = ();
+
+    #[allow(non_upper_case_globals)]
+    fn try_from(raw: FLAC__StreamDecoderErrorStatus) -> Result<FlacDecoderErrorStatus, ()> {
+        Ok(match raw {
+            FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_LOST_SYNC => FlacDecoderErrorStatus::LostSync,
+            FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_BAD_HEADER => FlacDecoderErrorStatus::BadHeader,
+            FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_FRAME_CRC_MISMATCH => FlacDecoderErrorStatus::FrameCrcMismatch,
+            FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_UNPARSEABLE_STREAM => FlacDecoderErrorStatus::UnparseableStream,
+            #[cfg(feature = "libflac-nobuild")]
+            FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_BAD_METADATA => FlacDecoderErrorStatus::BadMetadata,
+            #[cfg(feature = "libflac-nobuild")]
+            FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_OUT_OF_BOUNDS => FlacDecoderErrorStatus::OutOfBounds,
+            #[cfg(feature = "libflac-nobuild")]
+            FLAC__StreamDecoderErrorStatus_FLAC__STREAM_DECODER_ERROR_STATUS_MISSING_FRAME => FlacDecoderErrorStatus::MissingFrame,
+            _ => return Err(()),
+        })
+    }
+}
+
+/// Uses libFLAC's `FLAC__StreamDecoderErrorStatusString`.
+impl fmt::Display for FlacDecoderErrorStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = unsafe { CStr::from_ptr(*(ptr::addr_of!(FLAC__StreamDecoderErrorStatusString) as *const *const c_char).add(*self as usize)) };
+        f.write_str(&name.to_string_lossy())
+    }
+}
+
+
+/// Error returned when [`FlacDecoder::read_frame()`](struct.FlacDecoder.html#method.read_frame),
+/// [`process_until_end_of_metadata()`](struct.FlacDecoder.html#method.process_until_end_of_metadata),
+/// [`seek_to_sample()`](struct.FlacDecoder.html#method.seek_to_sample), or
+/// [`finish()`](struct.FlacDecoder.html#method.finish) fail.
+#[derive(Debug)]
+pub struct FlacDecoderError {
+    /// The state the decoder was left in.
+    pub state: FlacDecoderState,
+    /// The error returned by the input source, if that's what caused the failure.
+    ///
+    /// Only available for [`init_read_seek()`](struct.FlacDecoderConfig.html#method.init_read_seek)
+    /// and [`init_read_seek_ogg()`](struct.FlacDecoderConfig.html#method.init_read_seek_ogg),
+    /// since libFLAC handles I/O itself otherwise.
+    pub io_error: Option<io::Error>,
+    /// Whether [`finish()`](struct.FlacDecoder.html#method.finish) failed because
+    /// [MD5 checking](struct.FlacDecoderConfig.html#method.md5_checking) was on,
+    /// and the decoded audio's signature doesn't match the one in the STREAMINFO block.
+    pub md5_mismatch: bool,
+    /// The sample [`seek_to_sample()`](struct.FlacDecoder.html#method.seek_to_sample) was asked for,
+    /// if it failed because it's at or past the end of the stream.
+    pub seek_past_end: Option<u64>,
+}
+
+impl fmt::Display for FlacDecoderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.md5_mismatch {
+            return f.write_str("MD5 signature mismatch");
+        }
+        if let Some(sample) = self.seek_past_end {
+            return write!(f, "sample {} is past the end of the stream", sample);
+        }
+
+        write!(f, "{}", self.state)?;
+        if let Some(err) = self.io_error.as_ref() {
+            write!(f, ": {}", err)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for FlacDecoderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.io_error.as_ref().map(|err| err as _)
+    }
+}
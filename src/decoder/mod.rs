@@ -0,0 +1,39 @@
+mod callbacks;
+#[allow(clippy::module_inception)]
+mod decoder;
+mod config;
+mod state;
+mod error;
+
+#[cfg(feature = "flac")]
+use flac_sys::{FLAC__StreamDecoder, FLAC__stream_decoder_delete};
+
+#[cfg(feature = "libflac-nobuild")]
+use libflac_sys::{FLAC__StreamDecoder, FLAC__stream_decoder_delete};
+
+use std::{mem, ptr};
+
+pub use self::callbacks::{ReadSeekWrapper, ReadSeek, DecoderCallbackData, flac_decoder_read_seek_read_callback, flac_decoder_read_seek_seek_callback,
+                          flac_decoder_read_seek_tell_callback, flac_decoder_read_seek_length_callback, flac_decoder_read_seek_eof_callback,
+                          flac_decoder_write_callback, flac_decoder_metadata_callback, flac_decoder_error_callback};
+pub use self::error::{FlacDecoderInitError, FlacDecoderErrorStatus, FlacDecoderError};
+pub use self::config::FlacDecoderConfig;
+pub use self::state::FlacDecoderState;
+pub use self::decoder::FlacDecoder;
+
+
+/// The raw decoder and the settings libFLAC rejected.
+#[derive(Debug)]
+struct StreamDecoderContainer(pub *mut FLAC__StreamDecoder, pub Vec<&'static str>);
+
+impl Drop for StreamDecoderContainer {
+    fn drop(&mut self) {
+        let ptr = mem::replace(&mut self.0, ptr::null_mut());
+        if !ptr.is_null() {
+            unsafe { FLAC__stream_decoder_delete(ptr) };
+        }
+    }
+}
+
+// libFLAC stream decoders aren't tied to the thread that created them
+unsafe impl Send for StreamDecoderContainer {}
@@ -0,0 +1,99 @@
+#[cfg(feature = "flac")]
+use flac_sys::{FLAC__StreamDecoderState, FLAC__StreamDecoderStateString, FLAC__StreamDecoderState_FLAC__STREAM_DECODER_SEARCH_FOR_METADATA,
+               FLAC__StreamDecoderState_FLAC__STREAM_DECODER_READ_METADATA, FLAC__StreamDecoderState_FLAC__STREAM_DECODER_SEARCH_FOR_FRAME_SYNC,
+               FLAC__StreamDecoderState_FLAC__STREAM_DECODER_READ_FRAME, FLAC__StreamDecoderState_FLAC__STREAM_DECODER_END_OF_STREAM,
+               FLAC__StreamDecoderState_FLAC__STREAM_DECODER_OGG_ERROR, FLAC__StreamDecoderState_FLAC__STREAM_DECODER_SEEK_ERROR,
+               FLAC__StreamDecoderState_FLAC__STREAM_DECODER_ABORTED, FLAC__StreamDecoderState_FLAC__STREAM_DECODER_MEMORY_ALLOCATION_ERROR,
+               FLAC__StreamDecoderState_FLAC__STREAM_DECODER_UNINITIALIZED};
+
+#[cfg(feature = "libflac-nobuild")]
+use libflac_sys::{FLAC__StreamDecoderState, FLAC__StreamDecoderStateString,
+                  FLAC__STREAM_DECODER_SEARCH_FOR_METADATA as FLAC__StreamDecoderState_FLAC__STREAM_DECODER_SEARCH_FOR_METADATA,
+                  FLAC__STREAM_DECODER_READ_METADATA as FLAC__StreamDecoderState_FLAC__STREAM_DECODER_READ_METADATA,
+                  FLAC__STREAM_DECODER_SEARCH_FOR_FRAME_SYNC as FLAC__StreamDecoderState_FLAC__STREAM_DECODER_SEARCH_FOR_FRAME_SYNC,
+                  FLAC__STREAM_DECODER_READ_FRAME as FLAC__StreamDecoderState_FLAC__STREAM_DECODER_READ_FRAME,
+                  FLAC__STREAM_DECODER_END_OF_STREAM as FLAC__StreamDecoderState_FLAC__STREAM_DECODER_END_OF_STREAM,
+                  FLAC__STREAM_DECODER_OGG_ERROR as FLAC__StreamDecoderState_FLAC__STREAM_DECODER_OGG_ERROR,
+                  FLAC__STREAM_DECODER_SEEK_ERROR as FLAC__StreamDecoderState_FLAC__STREAM_DECODER_SEEK_ERROR,
+                  FLAC__STREAM_DECODER_ABORTED as FLAC__StreamDecoderState_FLAC__STREAM_DECODER_ABORTED,
+                  FLAC__STREAM_DECODER_MEMORY_ALLOCATION_ERROR as FLAC__StreamDecoderState_FLAC__STREAM_DECODER_MEMORY_ALLOCATION_ERROR,
+                  FLAC__STREAM_DECODER_UNINITIALIZED as FLAC__StreamDecoderState_FLAC__STREAM_DECODER_UNINITIALIZED};
+
+use std::os::raw::c_char;
+use std::convert::TryFrom;
+use std::ffi::CStr;
+use std::{fmt, ptr};
+
+
+/// State values for a FLAC stream decoder, like the one used by a [`FlacEncoder`](struct.FlacEncoder.html) in verify mode.
+///
+/// The verify decoder's state can be obtained by calling
+/// [`FlacEncoder::verify_decoder_state()`](struct.FlacEncoder.html#method.verify_decoder_state).
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum FlacDecoderState {
+    /// The decoder is ready to search for metadata.
+    SearchForMetadata = FLAC__StreamDecoderState_FLAC__STREAM_DECODER_SEARCH_FOR_METADATA,
+
+    /// The decoder is ready to or is in the process of reading metadata.
+    ReadMetadata = FLAC__StreamDecoderState_FLAC__STREAM_DECODER_READ_METADATA,
+
+    /// The decoder is ready to or is in the process of searching for the frame sync code.
+    SearchForFrameSync = FLAC__StreamDecoderState_FLAC__STREAM_DECODER_SEARCH_FOR_FRAME_SYNC,
+
+    /// The decoder is ready to or is in the process of reading a frame.
+    ReadFrame = FLAC__StreamDecoderState_FLAC__STREAM_DECODER_READ_FRAME,
+
+    /// The decoder has reached the end of the stream.
+    EndOfStream = FLAC__StreamDecoderState_FLAC__STREAM_DECODER_END_OF_STREAM,
+
+    /// An error occurred in the underlying Ogg layer.
+    OggError = FLAC__StreamDecoderState_FLAC__STREAM_DECODER_OGG_ERROR,
+
+    /// An error occurred while seeking. The decoder must be flushed or reset before decoding can continue.
+    SeekError = FLAC__StreamDecoderState_FLAC__STREAM_DECODER_SEEK_ERROR,
+
+    /// The decoder was aborted by the read or write callback.
+    Aborted = FLAC__StreamDecoderState_FLAC__STREAM_DECODER_ABORTED,
+
+    /// An error occurred allocating memory. The decoder is in an invalid state and can no longer be used.
+    MemoryAllocationError = FLAC__StreamDecoderState_FLAC__STREAM_DECODER_MEMORY_ALLOCATION_ERROR,
+
+    /// The decoder is in the uninitialized state.
+    Uninitialized = FLAC__StreamDecoderState_FLAC__STREAM_DECODER_UNINITIALIZED,
+}
+
+impl From<FlacDecoderState> for FLAC__StreamDecoderState {
+    fn from(val: FlacDecoderState) -> FLAC__StreamDecoderState {
+        val as FLAC__StreamDecoderState
+    }
+}
+
+impl TryFrom<FLAC__StreamDecoderState> for FlacDecoderState {
+    type Error = ();
+
+    #[allow(non_upper_case_globals)]
+    fn try_from(raw: FLAC__StreamDecoderState) -> Result<FlacDecoderState, ()> {
+        Ok(match raw {
+            FLAC__StreamDecoderState_FLAC__STREAM_DECODER_SEARCH_FOR_METADATA => FlacDecoderState::SearchForMetadata,
+            FLAC__StreamDecoderState_FLAC__STREAM_DECODER_READ_METADATA => FlacDecoderState::ReadMetadata,
+            FLAC__StreamDecoderState_FLAC__STREAM_DECODER_SEARCH_FOR_FRAME_SYNC => FlacDecoderState::SearchForFrameSync,
+            FLAC__StreamDecoderState_FLAC__STREAM_DECODER_READ_FRAME => FlacDecoderState::ReadFrame,
+            FLAC__StreamDecoderState_FLAC__STREAM_DECODER_END_OF_STREAM => FlacDecoderState::EndOfStream,
+            FLAC__StreamDecoderState_FLAC__STREAM_DECODER_OGG_ERROR => FlacDecoderState::OggError,
+            FLAC__StreamDecoderState_FLAC__STREAM_DECODER_SEEK_ERROR => FlacDecoderState::SeekError,
+            FLAC__StreamDecoderState_FLAC__STREAM_DECODER_ABORTED => FlacDecoderState::Aborted,
+            FLAC__StreamDecoderState_FLAC__STREAM_DECODER_MEMORY_ALLOCATION_ERROR => FlacDecoderState::MemoryAllocationError,
+            FLAC__StreamDecoderState_FLAC__STREAM_DECODER_UNINITIALIZED => FlacDecoderState::Uninitialized,
+            _ => return Err(()),
+        })
+    }
+}
+
+/// Uses libFLAC's `FLAC__StreamDecoderStateString`.
+impl fmt::Display for FlacDecoderState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = unsafe { CStr::from_ptr(*(ptr::addr_of!(FLAC__StreamDecoderStateString) as *const *const c_char).add(*self as usize)) };
+        f.write_str(&name.to_string_lossy())
+    }
+}
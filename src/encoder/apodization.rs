@@ -0,0 +1,360 @@
+use std::ffi::CString;
+use std::error::Error;
+use std::str::FromStr;
+use std::fmt;
+
+
+/// The maximum amount of window functions libFLAC keeps; the rest are dropped.
+const MAX_APODIZATIONS: usize = 32;
+
+
+/// A window function to apodize the signal with before LPC analysis,
+/// for use with [`FlacEncoderConfig::apodization()`](struct.FlacEncoderConfig.html#method.apodization).
+///
+/// libFLAC silently drops window functions it can't parse or whose parameters are out of range,
+/// so build the specification with [`specification()`](#method.specification), which validates them,
+/// or check existing ones with [`parse()`](#method.parse).
+///
+/// The `Display` implementation yields the specification of a single window function,
+/// and the `FromStr` implementation parses one.
+///
+/// # Examples
+///
+/// ```
+/// # use flac_bound::{ApodizationError, Apodization, FlacEncoder};
+/// let windows = [Apodization::Tukey(0.5), Apodization::PartialTukey { parts: 2, overlap: None, p: None }];
+/// let spec = Apodization::specification(&windows).unwrap();
+/// assert_eq!(spec.to_str(), Ok("tukey(0.5);partial_tukey(2)"));
+/// assert_eq!(Apodization::parse(spec.to_str().unwrap()), Ok(windows.to_vec()));
+///
+/// let conf = FlacEncoder::new().unwrap().apodization(&spec);
+/// # let _ = conf;
+///
+/// assert_eq!(Apodization::parse("tukey(0.5"),
+///            Err(ApodizationError::Parse { window: "tukey(0.5".to_string(), reason: "missing closing parenthesis".to_string() }));
+/// assert_eq!(Apodization::parse("hann;gauss(0.7)").unwrap_err().to_string(),
+///            "illegal apodization: gauss(0.7): STDDEV must be in (0, 0.5]");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Apodization {
+    /// `bartlett`
+    Bartlett,
+    /// `bartlett_hann`
+    BartlettHann,
+    /// `blackman`
+    Blackman,
+    /// `blackman_harris_4term_92db`
+    BlackmanHarris4Term92Db,
+    /// `connes`
+    Connes,
+    /// `flattop`
+    Flattop,
+    /// `gauss(STDDEV)`, where `0 < STDDEV <= 0.5`.
+    Gauss(f32),
+    /// `hamming`
+    Hamming,
+    /// `hann`
+    Hann,
+    /// `kaiser_bessel`
+    KaiserBessel,
+    /// `nuttall`
+    Nuttall,
+    /// `rectangle`
+    Rectangle,
+    /// `triangle`
+    Triangle,
+    /// `tukey(P)`, where `0 <= P <= 1`; `0` is a rectangle and `1` a Hann window.
+    Tukey(f32),
+    /// `partial_tukey(n[/ov[/P]])`: `n` Tukey windows, each covering a part of the block,
+    /// overlapping by `ov` (`ov < 1`, negative for gaps between them, default `0.1`), with the specified `P` (default `0.2`).
+    ///
+    /// Each part counts as a separate window function towards libFLAC's limit of 32.
+    PartialTukey {
+        /// Amount of windows, `n`.
+        parts: u32,
+        /// Overlap between consecutive windows, `ov`.
+        overlap: Option<f32>,
+        /// Tukey parameter, `P`.
+        p: Option<f32>,
+    },
+    /// `punchout_tukey(n[/ov[/P]])`: `n` Tukey windows, each covering all but a part of the block,
+    /// overlapping by `ov` (`ov < 1`, negative for gaps between them, default `0.2`), with the specified `P` (default `0.2`).
+    ///
+    /// Each part counts as a separate window function towards libFLAC's limit of 32.
+    PunchoutTukey {
+        /// Amount of windows, `n`.
+        parts: u32,
+        /// Overlap between consecutive windows, `ov`.
+        overlap: Option<f32>,
+        /// Tukey parameter, `P`.
+        p: Option<f32>,
+    },
+    /// `subdivide_tukey(n[/P])`: Tukey windows over the whole block, halves, &c., up to `n`ths of the block (`n > 1`),
+    /// with the specified `P` (default `0.5`).
+    ///
+    /// **Note**:<br />
+    /// Requires libFLAC ≥ 1.4.0, so it's only available with the `libflac-sys` back-end;
+    /// older libFLACs silently ignore it, so [`parse()`](#method.parse) rejects it on the `flac-sys` back-end.
+    #[cfg(feature = "libflac-nobuild")]
+    SubdivideTukey {
+        /// Amount of subdivisions, `n`.
+        parts: u32,
+        /// Tukey parameter, `P`.
+        p: Option<f32>,
+    },
+    /// `welch`
+    Welch,
+}
+
+impl Apodization {
+    /// Check that the parameters of this window function are in the range libFLAC accepts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use flac_bound::Apodization;
+    /// assert!(Apodization::PartialTukey { parts: 2, overlap: Some(-0.5), p: None }.validate().is_ok());
+    /// assert_eq!(Apodization::parse("partial_tukey(2/-0.5)"), Ok(vec![Apodization::PartialTukey { parts: 2, overlap: Some(-0.5), p: None }]));
+    /// assert_eq!(Apodization::parse("punchout_tukey(3/1)").unwrap_err().to_string(),
+    ///            "illegal apodization: punchout_tukey(3/1): ov must be finite and less than 1");
+    ///
+    /// #[cfg(feature = "libflac-nobuild")]
+    /// assert_eq!(Apodization::parse("subdivide_tukey(3)"), Ok(vec![Apodization::SubdivideTukey { parts: 3, p: None }]));
+    /// #[cfg(feature = "flac")]
+    /// assert!(Apodization::parse("subdivide_tukey(3)").is_err());
+    /// ```
+    pub fn validate(&self) -> Result<(), ApodizationError> {
+        let illegal = |reason: &str| Err(ApodizationError::Illegal(format!("{}: {}", self, reason)));
+        let valid_p = |p: Option<f32>| p.map(|p| (0.0..=1.0).contains(&p)).unwrap_or(true);
+        let valid_overlap = |overlap: Option<f32>| overlap.map(|ov| ov < 1.0 && ov.is_finite()).unwrap_or(true);
+
+        match *self {
+            Apodization::Gauss(stddev) if !(stddev > 0.0 && stddev <= 0.5) => illegal("STDDEV must be in (0, 0.5]"),
+            Apodization::Tukey(p) if !valid_p(Some(p)) => illegal("P must be in [0, 1]"),
+            Apodization::PartialTukey { parts, overlap, p } |
+            Apodization::PunchoutTukey { parts, overlap, p } => {
+                if parts == 0 || parts as usize >= MAX_APODIZATIONS {
+                    illegal("n must be in [1, 31]")
+                } else if !valid_overlap(overlap) {
+                    illegal("ov must be finite and less than 1")
+                } else if !valid_p(p) {
+                    illegal("P must be in [0, 1]")
+                } else {
+                    Ok(())
+                }
+            }
+            #[cfg(feature = "libflac-nobuild")]
+            Apodization::SubdivideTukey { parts, p } => {
+                if parts < 2 {
+                    illegal("n must be at least 2")
+                } else if !valid_p(p) {
+                    illegal("P must be in [0, 1]")
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Validate the specified window functions and join them into a specification suitable for
+    /// [`FlacEncoderConfig::apodization()`](struct.FlacEncoderConfig.html#method.apodization).
+    ///
+    /// Also fails if they'd take up more than the 32 window functions libFLAC keeps.
+    pub fn specification(windows: &[Apodization]) -> Result<CString, ApodizationError> {
+        let mut slots = 0;
+        let mut ret = String::new();
+        for window in windows {
+            window.validate()?;
+
+            // libFLAC only splits a multi-part window if all of its parts fit with one slot to spare
+            let remaining = MAX_APODIZATIONS - slots;
+            if window.slots() > remaining || (window.slots() > 1 && window.slots() == remaining) {
+                return Err(ApodizationError::Illegal(format!("{}: doesn't fit in the remaining {} of {} window function slots", window, remaining, MAX_APODIZATIONS)));
+            }
+            slots += window.slots();
+
+            if !ret.is_empty() {
+                ret.push(';');
+            }
+            ret += &window.to_string();
+        }
+        Ok(CString::new(ret).expect("no NULs in rendered apodization"))
+    }
+
+    /// Parse and validate a `;`-separated specification, as accepted by
+    /// [`FlacEncoderConfig::apodization()`](struct.FlacEncoderConfig.html#method.apodization).
+    ///
+    /// Empty window functions are skipped, like libFLAC does.
+    pub fn parse(specification: &str) -> Result<Vec<Apodization>, ApodizationError> {
+        let windows = specification.split(';').filter(|w| !w.is_empty()).map(str::parse).collect::<Result<Vec<_>, _>>()?;
+        Apodization::specification(&windows)?;
+        Ok(windows)
+    }
+
+    /// How many of libFLAC's window function slots this takes up.
+    fn slots(&self) -> usize {
+        match *self {
+            Apodization::PartialTukey { parts, .. } |
+            Apodization::PunchoutTukey { parts, .. } => (parts as usize).max(1),
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for Apodization {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Apodization::Bartlett => f.write_str("bartlett"),
+            Apodization::BartlettHann => f.write_str("bartlett_hann"),
+            Apodization::Blackman => f.write_str("blackman"),
+            Apodization::BlackmanHarris4Term92Db => f.write_str("blackman_harris_4term_92db"),
+            Apodization::Connes => f.write_str("connes"),
+            Apodization::Flattop => f.write_str("flattop"),
+            Apodization::Gauss(stddev) => write!(f, "gauss({})", stddev),
+            Apodization::Hamming => f.write_str("hamming"),
+            Apodization::Hann => f.write_str("hann"),
+            Apodization::KaiserBessel => f.write_str("kaiser_bessel"),
+            Apodization::Nuttall => f.write_str("nuttall"),
+            Apodization::Rectangle => f.write_str("rectangle"),
+            Apodization::Triangle => f.write_str("triangle"),
+            Apodization::Tukey(p) => write!(f, "tukey({})", p),
+            Apodization::PartialTukey { parts, overlap, p } => write_multiple_tukey(f, "partial_tukey", parts, overlap, p, 0.1),
+            Apodization::PunchoutTukey { parts, overlap, p } => write_multiple_tukey(f, "punchout_tukey", parts, overlap, p, 0.2),
+            #[cfg(feature = "libflac-nobuild")]
+            Apodization::SubdivideTukey { parts, p } => {
+                write!(f, "subdivide_tukey({}", parts)?;
+                if let Some(p) = p {
+                    write!(f, "/{}", p)?;
+                }
+                f.write_str(")")
+            }
+            Apodization::Welch => f.write_str("welch"),
+        }
+    }
+}
+
+/// `P` can only be specified after `ov`, so the default overlap is written out if only the former is set.
+fn write_multiple_tukey(f: &mut fmt::Formatter, name: &str, parts: u32, overlap: Option<f32>, p: Option<f32>, default_overlap: f32) -> fmt::Result {
+    write!(f, "{}({}", name, parts)?;
+    match (overlap, p) {
+        (None, None) => {}
+        (Some(overlap), None) => write!(f, "/{}", overlap)?,
+        (overlap, Some(p)) => write!(f, "/{}/{}", overlap.unwrap_or(default_overlap), p)?,
+    }
+    f.write_str(")")
+}
+
+impl FromStr for Apodization {
+    type Err = ApodizationError;
+
+    /// Parse the specification of a single window function, without validating its parameters' ranges.
+    fn from_str(s: &str) -> Result<Apodization, ApodizationError> {
+        let err = |reason: &str| {
+            ApodizationError::Parse {
+                window: s.to_string(),
+                reason: reason.to_string(),
+            }
+        };
+
+        let (name, params) = match s.find('(') {
+            Some(idx) => {
+                if !s.ends_with(')') {
+                    return Err(err("missing closing parenthesis"));
+                }
+                (&s[..idx], Some(s[idx + 1..s.len() - 1].split('/').collect::<Vec<_>>()))
+            }
+            None => (s, None),
+        };
+
+        let param_count = |min: usize, max: usize| -> Result<Vec<&str>, ApodizationError> {
+            match params.as_ref() {
+                Some(params) if params.len() >= min && params.len() <= max => Ok(params.clone()),
+                Some(_) if min == max => Err(err(&format!("{} takes {} parameter(s)", name, min))),
+                Some(_) => Err(err(&format!("{} takes {} to {} parameters", name, min, max))),
+                None => Err(err(&format!("{} requires parameters", name))),
+            }
+        };
+        let float = |param: &str| param.parse::<f32>().map_err(|_| err(&format!("invalid number \"{}\"", param)));
+        let integer = |param: &str| param.parse::<u32>().map_err(|_| err(&format!("invalid integer \"{}\"", param)));
+
+        let ret = match name {
+            "gauss" => Apodization::Gauss(float(param_count(1, 1)?[0])?),
+            "tukey" => Apodization::Tukey(float(param_count(1, 1)?[0])?),
+            "partial_tukey" | "punchout_tukey" => {
+                let params = param_count(1, 3)?;
+                let parts = integer(params[0])?;
+                let overlap = params.get(1).map(|ov| float(ov)).transpose()?;
+                let p = params.get(2).map(|p| float(p)).transpose()?;
+                if name == "partial_tukey" {
+                    Apodization::PartialTukey { parts, overlap, p }
+                } else {
+                    Apodization::PunchoutTukey { parts, overlap, p }
+                }
+            }
+            #[cfg(feature = "flac")]
+            "subdivide_tukey" => return Err(err("subdivide_tukey requires libFLAC 1.4.0, i.e. the libflac-sys back-end")),
+            #[cfg(feature = "libflac-nobuild")]
+            "subdivide_tukey" => {
+                let params = param_count(1, 2)?;
+                Apodization::SubdivideTukey {
+                    parts: integer(params[0])?,
+                    p: params.get(1).map(|p| float(p)).transpose()?,
+                }
+            }
+            _ => {
+                if params.is_some() {
+                    return Err(match name {
+                        "bartlett" | "bartlett_hann" | "blackman" | "blackman_harris_4term_92db" | "connes" | "flattop" | "hamming" | "hann" |
+                        "kaiser_bessel" | "nuttall" | "rectangle" | "triangle" | "welch" => err(&format!("{} takes no parameters", name)),
+                        _ => err("unknown window function"),
+                    });
+                }
+                match name {
+                    "bartlett" => Apodization::Bartlett,
+                    "bartlett_hann" => Apodization::BartlettHann,
+                    "blackman" => Apodization::Blackman,
+                    "blackman_harris_4term_92db" => Apodization::BlackmanHarris4Term92Db,
+                    "connes" => Apodization::Connes,
+                    "flattop" => Apodization::Flattop,
+                    "hamming" => Apodization::Hamming,
+                    "hann" => Apodization::Hann,
+                    "kaiser_bessel" => Apodization::KaiserBessel,
+                    "nuttall" => Apodization::Nuttall,
+                    "rectangle" => Apodization::Rectangle,
+                    "triangle" => Apodization::Triangle,
+                    "welch" => Apodization::Welch,
+                    _ => return Err(err("unknown window function")),
+                }
+            }
+        };
+        Ok(ret)
+    }
+}
+
+
+/// Errors that can arise when parsing or validating an [`Apodization`](enum.Apodization.html).
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum ApodizationError {
+    /// The specification of a window function is malformed.
+    Parse {
+        /// The malformed window function specification.
+        window: String,
+        /// What's wrong with it.
+        reason: String,
+    },
+
+    /// The window function is illegal for the contained reason.
+    Illegal(String),
+}
+
+impl fmt::Display for ApodizationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ApodizationError::Parse { window, reason } => write!(f, "window function \"{}\": {}", window, reason),
+            ApodizationError::Illegal(violation) => write!(f, "illegal apodization: {}", violation),
+        }
+    }
+}
+
+impl Error for ApodizationError {}
@@ -1,15 +1,100 @@
 #[cfg(feature = "flac")]
 use flac_sys::{FLAC__StreamEncoder, FLAC__StreamEncoderWriteStatus, FLAC__StreamEncoderWriteStatus_FLAC__STREAM_ENCODER_WRITE_STATUS_OK,
-               FLAC__StreamEncoderWriteStatus_FLAC__STREAM_ENCODER_WRITE_STATUS_FATAL_ERROR};
+               FLAC__StreamEncoderWriteStatus_FLAC__STREAM_ENCODER_WRITE_STATUS_FATAL_ERROR, FLAC__StreamEncoderSeekStatus,
+               FLAC__StreamEncoderSeekStatus_FLAC__STREAM_ENCODER_SEEK_STATUS_OK, FLAC__StreamEncoderSeekStatus_FLAC__STREAM_ENCODER_SEEK_STATUS_ERROR,
+               FLAC__StreamEncoderTellStatus, FLAC__StreamEncoderTellStatus_FLAC__STREAM_ENCODER_TELL_STATUS_OK,
+               FLAC__StreamEncoderTellStatus_FLAC__STREAM_ENCODER_TELL_STATUS_ERROR};
 
 #[cfg(feature = "libflac-nobuild")]
 use libflac_sys::{FLAC__StreamEncoder, FLAC__StreamEncoderWriteStatus,
                   FLAC__STREAM_ENCODER_WRITE_STATUS_OK as FLAC__StreamEncoderWriteStatus_FLAC__STREAM_ENCODER_WRITE_STATUS_OK,
-                  FLAC__STREAM_ENCODER_WRITE_STATUS_FATAL_ERROR as FLAC__StreamEncoderWriteStatus_FLAC__STREAM_ENCODER_WRITE_STATUS_FATAL_ERROR};
+                  FLAC__STREAM_ENCODER_WRITE_STATUS_FATAL_ERROR as FLAC__StreamEncoderWriteStatus_FLAC__STREAM_ENCODER_WRITE_STATUS_FATAL_ERROR,
+                  FLAC__StreamEncoderSeekStatus,
+                  FLAC__STREAM_ENCODER_SEEK_STATUS_OK as FLAC__StreamEncoderSeekStatus_FLAC__STREAM_ENCODER_SEEK_STATUS_OK,
+                  FLAC__STREAM_ENCODER_SEEK_STATUS_ERROR as FLAC__StreamEncoderSeekStatus_FLAC__STREAM_ENCODER_SEEK_STATUS_ERROR,
+                  FLAC__StreamEncoderTellStatus,
+                  FLAC__STREAM_ENCODER_TELL_STATUS_OK as FLAC__StreamEncoderTellStatus_FLAC__STREAM_ENCODER_TELL_STATUS_OK,
+                  FLAC__STREAM_ENCODER_TELL_STATUS_ERROR as FLAC__StreamEncoderTellStatus_FLAC__STREAM_ENCODER_TELL_STATUS_ERROR};
 
+use std::panic::{self, AssertUnwindSafe};
+use std::io::{self, Write, Seek, SeekFrom};
 use std::os::raw::{c_uint, c_void};
-use std::io::Write;
-use std::slice;
+use std::ptr::NonNull;
+use std::{fmt, slice};
+use std::any::Any;
+
+
+/// Called with `(bytes_written, samples_written, frames_written, total_frames_estimate)` after each frame is written.
+pub type ProgressCallback<'out> = dyn FnMut(u64, u64, u32, u32) + 'out;
+
+/// Rust-side state owned by an initialised [`FlacEncoder`](struct.FlacEncoder.html) and handed to the callbacks that need it.
+///
+/// Boxed so its address remains stable for libFLAC, which holds on to it as `client_data`.
+///
+/// The sinks are borrowed for `'out`, as enforced by the [`FlacEncoder`](struct.FlacEncoder.html).
+#[derive(Default)]
+pub struct EncoderCallbackData<'out> {
+    /// The sink for [`init_write()`](struct.FlacEncoderConfig.html#method.init_write)
+    /// and [`init_write_ogg()`](struct.FlacEncoderConfig.html#method.init_write_ogg).
+    pub write: Option<NonNull<WriteWrapper<'static>>>,
+    /// The sink for [`init_write_seek()`](struct.FlacEncoderConfig.html#method.init_write_seek).
+    pub write_seek: Option<NonNull<WriteSeekWrapper<'static>>>,
+    /// Called after each frame is written.
+    pub progress: Option<Box<ProgressCallback<'out>>>,
+    /// The first error returned by the sink, to be handed to the caller once control returns to Rust.
+    pub io_error: Option<io::Error>,
+    /// The payload of a panic caught in a callback, to be resumed once control returns to Rust.
+    pub panic: Option<Box<dyn Any + Send + 'static>>,
+}
+
+impl<'out> EncoderCallbackData<'out> {
+    /// Resume the unwind of a panic caught in one of the callbacks, if any.
+    pub fn resume_panic(&mut self) {
+        if let Some(payload) = self.panic.take() {
+            panic::resume_unwind(payload);
+        }
+    }
+
+    /// Call into the sink, catching a panic, since unwinding into libFLAC is undefined behaviour.
+    ///
+    /// Returns `None` if the sink errored, panicked, or if a callback had already panicked, in which case it isn't called again.
+    pub fn call_sink<T, F: FnOnce(&mut EncoderCallbackData<'out>) -> io::Result<T>>(&mut self, f: F) -> Option<T> {
+        if self.panic.is_some() {
+            return None;
+        }
+
+        match panic::catch_unwind(AssertUnwindSafe(|| f(self))) {
+            Ok(Ok(ret)) => Some(ret),
+            Ok(Err(err)) => {
+                self.store_io_error(err);
+                None
+            }
+            Err(payload) => {
+                self.panic = Some(payload);
+                None
+            }
+        }
+    }
+
+    /// Remember the specified error, unless an earlier one is already stored.
+    pub fn store_io_error(&mut self, err: io::Error) {
+        if self.io_error.is_none() {
+            self.io_error = Some(err);
+        }
+    }
+}
+
+impl<'out> fmt::Debug for EncoderCallbackData<'out> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EncoderCallbackData")
+            .field("write", &self.write.as_ref().map(|_| ".."))
+            .field("write_seek", &self.write_seek.as_ref().map(|_| ".."))
+            .field("progress", &self.progress.as_ref().map(|_| ".."))
+            .field("io_error", &self.io_error)
+            .field("panic", &self.panic.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
 
 
 /// This wrapper is necessary due to [fat pointers](https://chat.stackoverflow.com/transcript/message/47940937#47940937).
@@ -25,16 +110,123 @@ use std::slice;
 ///
 /// outs.process_interleaved(&[0xA1, 0xF3], 1).unwrap();
 /// ```
+///
+/// If the stream panics, libFLAC is told the write failed, the stream isn't called again,
+/// and the panic is resumed once control returns from the call that triggered it:
+///
+/// ```
+/// # use flac_bound::{WriteWrapper, FlacEncoder};
+/// # use std::io::{self, Write};
+/// # use std::panic;
+/// struct Fragile(usize);
+/// impl Write for Fragile {
+///     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+///         self.0 += buf.len();
+///         if self.0 > 1024 {
+///             panic!("full");
+///         }
+///         Ok(buf.len())
+///     }
+///
+///     fn flush(&mut self) -> io::Result<()> {
+///         Ok(())
+///     }
+/// }
+///
+/// let mut outf = Fragile(0);
+/// let mut outw = WriteWrapper(&mut outf);
+/// let mut outs = FlacEncoder::new().unwrap().channels(1).init_write(&mut outw).unwrap();
+///
+/// let noise: Vec<_> = (0..8192).map(|i| (i * 7919 % 65536) - 32768).collect();
+/// let err = panic::catch_unwind(panic::AssertUnwindSafe(|| outs.process(&[&noise]))).unwrap_err();
+/// assert_eq!(err.downcast_ref::<&str>(), Some(&"full"));
+/// ```
 pub struct WriteWrapper<'out>(pub &'out mut dyn Write);
 
 
 pub unsafe extern "C" fn flac_encoder_write_write_callback(_: *const FLAC__StreamEncoder, buffer: *const u8, bytes: usize, _: c_uint, _: c_uint,
                                                            client_data: *mut c_void)
                                                            -> FLAC__StreamEncoderWriteStatus {
-    let out = &mut (*(client_data as *mut WriteWrapper<'static>)).0;
+    let data = &mut *(client_data as *mut EncoderCallbackData<'static>);
+
+    match data.call_sink(|data| (*data.write.expect("no sink").as_ptr()).0.write_all(slice::from_raw_parts(buffer, bytes))) {
+        Some(()) => FLAC__StreamEncoderWriteStatus_FLAC__STREAM_ENCODER_WRITE_STATUS_OK,
+        None => FLAC__StreamEncoderWriteStatus_FLAC__STREAM_ENCODER_WRITE_STATUS_FATAL_ERROR,
+    }
+}
+
+
+/// A sink that can be both written to and seeked in, for use with [`WriteSeekWrapper`](struct.WriteSeekWrapper.html).
+///
+/// Automatically implemented for all `Write + Seek` types.
+pub trait WriteSeek: Write + Seek {}
+
+impl<T: Write + Seek + ?Sized> WriteSeek for T {}
+
+/// Like [`WriteWrapper`](struct.WriteWrapper.html), but for seekable sinks.
+///
+/// Encoders initialised with one of these can go back and rewrite the STREAMINFO block
+/// (MD5 signature, total sample count, min/max frame sizes) when finished.
+///
+/// # Examples
+///
+/// ```
+/// # use flac_bound::{WriteSeekWrapper, FlacEncoder};
+/// # use std::io::Cursor;
+/// let mut outv = Cursor::new(Vec::new());
+/// let mut outw = WriteSeekWrapper(&mut outv);
+/// let mut outs = FlacEncoder::new().unwrap().init_write_seek(&mut outw).unwrap();
+///
+/// outs.process_interleaved(&[0xA1, 0xF3], 1).unwrap();
+/// ```
+pub struct WriteSeekWrapper<'out>(pub &'out mut dyn WriteSeek);
+
+
+pub unsafe extern "C" fn flac_encoder_write_seek_write_callback(_: *const FLAC__StreamEncoder, buffer: *const u8, bytes: usize, _: c_uint, _: c_uint,
+                                                                client_data: *mut c_void)
+                                                                -> FLAC__StreamEncoderWriteStatus {
+    let data = &mut *(client_data as *mut EncoderCallbackData<'static>);
+
+    match data.call_sink(|data| (*data.write_seek.expect("no sink").as_ptr()).0.write_all(slice::from_raw_parts(buffer, bytes))) {
+        Some(()) => FLAC__StreamEncoderWriteStatus_FLAC__STREAM_ENCODER_WRITE_STATUS_OK,
+        None => FLAC__StreamEncoderWriteStatus_FLAC__STREAM_ENCODER_WRITE_STATUS_FATAL_ERROR,
+    }
+}
+
+pub unsafe extern "C" fn flac_encoder_write_seek_seek_callback(_: *const FLAC__StreamEncoder, absolute_byte_offset: u64, client_data: *mut c_void)
+                                                               -> FLAC__StreamEncoderSeekStatus {
+    let data = &mut *(client_data as *mut EncoderCallbackData<'static>);
+
+    match data.call_sink(|data| (*data.write_seek.expect("no sink").as_ptr()).0.seek(SeekFrom::Start(absolute_byte_offset))) {
+        Some(_) => FLAC__StreamEncoderSeekStatus_FLAC__STREAM_ENCODER_SEEK_STATUS_OK,
+        None => FLAC__StreamEncoderSeekStatus_FLAC__STREAM_ENCODER_SEEK_STATUS_ERROR,
+    }
+}
+
+pub unsafe extern "C" fn flac_encoder_write_seek_tell_callback(_: *const FLAC__StreamEncoder, absolute_byte_offset: *mut u64, client_data: *mut c_void)
+                                                               -> FLAC__StreamEncoderTellStatus {
+    let data = &mut *(client_data as *mut EncoderCallbackData<'static>);
+
+    match data.call_sink(|data| (*data.write_seek.expect("no sink").as_ptr()).0.stream_position()) {
+        Some(pos) => {
+            *absolute_byte_offset = pos;
+            FLAC__StreamEncoderTellStatus_FLAC__STREAM_ENCODER_TELL_STATUS_OK
+        }
+        None => FLAC__StreamEncoderTellStatus_FLAC__STREAM_ENCODER_TELL_STATUS_ERROR,
+    }
+}
+
+
+pub unsafe extern "C" fn flac_encoder_progress_callback(_: *const FLAC__StreamEncoder, bytes_written: u64, samples_written: u64, frames_written: c_uint,
+                                                        total_frames_estimate: c_uint, client_data: *mut c_void) {
+    let data = &mut *(client_data as *mut EncoderCallbackData<'static>);
+    if data.panic.is_some() {
+        return;
+    }
 
-    match out.write_all(slice::from_raw_parts(buffer, bytes)) {
-        Ok(_) => FLAC__StreamEncoderWriteStatus_FLAC__STREAM_ENCODER_WRITE_STATUS_OK,
-        Err(_) => FLAC__StreamEncoderWriteStatus_FLAC__STREAM_ENCODER_WRITE_STATUS_FATAL_ERROR,
+    if let Some(progress) = data.progress.as_mut() {
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| progress(bytes_written, samples_written, frames_written, total_frames_estimate))) {
+            data.panic = Some(payload);
+        }
     }
 }
@@ -1,5 +1,5 @@
 #[cfg(feature = "flac")]
-use flac_sys::{FLAC__StreamEncoderInitStatus, FLAC__bool, FLAC__stream_encoder_set_ogg_serial_number, FLAC__stream_encoder_set_verify,
+use flac_sys::{FLAC__StreamEncoderInitStatus, FLAC__StreamEncoderProgressCallback, FLAC__bool, FLAC__stream_encoder_set_ogg_serial_number, FLAC__stream_encoder_set_verify,
                FLAC__stream_encoder_set_streamable_subset, FLAC__stream_encoder_set_channels, FLAC__stream_encoder_set_bits_per_sample,
                FLAC__stream_encoder_set_sample_rate, FLAC__stream_encoder_set_compression_level, FLAC__stream_encoder_set_blocksize,
                FLAC__stream_encoder_set_do_mid_side_stereo, FLAC__stream_encoder_set_loose_mid_side_stereo, FLAC__stream_encoder_set_apodization,
@@ -7,12 +7,13 @@ use flac_sys::{FLAC__StreamEncoderInitStatus, FLAC__bool, FLAC__stream_encoder_s
                FLAC__stream_encoder_set_do_escape_coding, FLAC__stream_encoder_set_do_exhaustive_model_search,
                FLAC__stream_encoder_set_min_residual_partition_order, FLAC__stream_encoder_set_max_residual_partition_order,
                FLAC__stream_encoder_set_rice_parameter_search_dist,
-               FLAC__stream_encoder_set_total_samples_estimate /* , FLAC__stream_encoder_set_metadata */, FLAC__stream_encoder_init_stream,
+               FLAC__stream_encoder_set_total_samples_estimate, FLAC__stream_encoder_set_metadata, FLAC__stream_encoder_init_stream,
                FLAC__stream_encoder_init_ogg_stream, FLAC__stream_encoder_init_file, FLAC__stream_encoder_init_ogg_file,
-               FLAC__StreamEncoderInitStatus_FLAC__STREAM_ENCODER_INIT_STATUS_OK};
+               FLAC__stream_encoder_get_state, FLAC__stream_encoder_finish, FLAC__StreamEncoderInitStatus_FLAC__STREAM_ENCODER_INIT_STATUS_OK,
+               FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_UNINITIALIZED};
 
 #[cfg(feature = "libflac-nobuild")]
-use libflac_sys::{FLAC__StreamEncoderInitStatus, FLAC__bool, FLAC__stream_encoder_set_ogg_serial_number, FLAC__stream_encoder_set_verify,
+use libflac_sys::{FLAC__StreamEncoderInitStatus, FLAC__StreamEncoderProgressCallback, FLAC__bool, FLAC__stream_encoder_set_verify,
                   FLAC__stream_encoder_set_streamable_subset, FLAC__stream_encoder_set_channels, FLAC__stream_encoder_set_bits_per_sample,
                   FLAC__stream_encoder_set_sample_rate, FLAC__stream_encoder_set_compression_level, FLAC__stream_encoder_set_blocksize,
                   FLAC__stream_encoder_set_do_mid_side_stereo, FLAC__stream_encoder_set_loose_mid_side_stereo, FLAC__stream_encoder_set_apodization,
@@ -20,23 +21,66 @@ use libflac_sys::{FLAC__StreamEncoderInitStatus, FLAC__bool, FLAC__stream_encode
                   FLAC__stream_encoder_set_do_escape_coding, FLAC__stream_encoder_set_do_exhaustive_model_search,
                   FLAC__stream_encoder_set_min_residual_partition_order, FLAC__stream_encoder_set_max_residual_partition_order,
                   FLAC__stream_encoder_set_limit_min_bitrate, FLAC__stream_encoder_set_rice_parameter_search_dist,
-                  FLAC__stream_encoder_set_total_samples_estimate /* , FLAC__stream_encoder_set_metadata */, FLAC__stream_encoder_init_stream,
-                  FLAC__stream_encoder_init_ogg_stream, FLAC__stream_encoder_init_file, FLAC__stream_encoder_init_ogg_file,
-                  FLAC__STREAM_ENCODER_INIT_STATUS_OK as FLAC__StreamEncoderInitStatus_FLAC__STREAM_ENCODER_INIT_STATUS_OK};
+                  FLAC__stream_encoder_set_total_samples_estimate, FLAC__stream_encoder_set_metadata, FLAC__stream_encoder_init_stream,
+                  FLAC__stream_encoder_init_file, FLAC__stream_encoder_get_state, FLAC__stream_encoder_finish,
+                  FLAC__STREAM_ENCODER_INIT_STATUS_OK as FLAC__StreamEncoderInitStatus_FLAC__STREAM_ENCODER_INIT_STATUS_OK,
+                  FLAC__STREAM_ENCODER_UNINITIALIZED as FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_UNINITIALIZED};
 
+#[cfg(all(feature = "libflac-nobuild", not(feature = "libflac-noogg")))]
+use libflac_sys::{FLAC__stream_encoder_set_ogg_serial_number, FLAC__stream_encoder_init_ogg_stream, FLAC__stream_encoder_init_ogg_file};
 
-use super::{StreamEncoderContainer, FlacEncoderInitError, WriteWrapper, FlacEncoder, flac_encoder_write_write_callback};
-use std::os::raw::{c_long, c_void};
-use std::ffi::{CString, CStr};
+
+#[cfg(feature = "flac")]
+use flac_sys::{FLAC__format_sample_rate_is_valid, FLAC__format_sample_rate_is_subset, FLAC__format_blocksize_is_subset, FLAC__MAX_CHANNELS,
+               FLAC__MIN_BITS_PER_SAMPLE, FLAC__REFERENCE_CODEC_MAX_BITS_PER_SAMPLE, FLAC__MIN_BLOCK_SIZE, FLAC__MAX_BLOCK_SIZE, FLAC__MAX_LPC_ORDER,
+               FLAC__MIN_QLP_COEFF_PRECISION, FLAC__MAX_QLP_COEFF_PRECISION, FLAC__SUBSET_MAX_LPC_ORDER_48000HZ,
+               FLAC__SUBSET_MAX_RICE_PARTITION_ORDER};
+
+#[cfg(feature = "libflac-nobuild")]
+use libflac_sys::{FLAC__format_sample_rate_is_valid, FLAC__format_sample_rate_is_subset, FLAC__format_blocksize_is_subset, FLAC__MAX_CHANNELS,
+                  FLAC__MIN_BITS_PER_SAMPLE, FLAC__REFERENCE_CODEC_MAX_BITS_PER_SAMPLE, FLAC__MIN_BLOCK_SIZE, FLAC__MAX_BLOCK_SIZE, FLAC__MAX_LPC_ORDER,
+                  FLAC__MIN_QLP_COEFF_PRECISION, FLAC__MAX_QLP_COEFF_PRECISION, FLAC__SUBSET_MAX_LPC_ORDER_48000HZ,
+                  FLAC__SUBSET_MAX_RICE_PARTITION_ORDER};
+
+use util;
+use metadata::{SeekTableTemplate, VorbisComment, FlacMetadata, CueSheet, Picture};
+use super::{StreamEncoderContainer, FlacEncoderConfigError, FlacEncoderInitError, EncoderCallbackData, EncoderSettings, WriteWrapper, WriteSeekWrapper,
+            FlacEncoder, flac_encoder_write_write_callback, flac_encoder_write_seek_write_callback, flac_encoder_write_seek_seek_callback,
+            flac_encoder_write_seek_tell_callback, flac_encoder_progress_callback};
+#[cfg(not(feature = "libflac-noogg"))]
+use std::os::raw::c_long;
+use std::os::raw::c_void;
+use std::ffi::CStr;
 use std::marker::PhantomData;
 use std::convert::TryFrom;
+use std::ptr::NonNull;
 use std::path::Path;
-use std::ptr;
+use std::{mem, ptr};
 
 
 /// Wrapper around a FLAC encoder for configuring the output settings.
 ///
-/// `FILE*` constructors unsupported, Write+Seek constructors unsupportable due to <https://github.com/rust-lang/rfcs/issues/2035>
+/// `FILE*` constructors unsupported, Write+Seek constructors go through [`WriteSeekWrapper`](struct.WriteSeekWrapper.html)
+/// due to <https://github.com/rust-lang/rfcs/issues/2035>
+///
+/// The config is `Send`, since libFLAC encoders aren't tied to a thread,
+/// so it can be set up on one thread and initialised on another.
+///
+/// # Examples
+///
+/// ```
+/// # use flac_bound::FlacEncoder;
+/// # use std::thread;
+/// let conf = FlacEncoder::new().unwrap().channels(1).compression_level(5);
+/// let out = thread::spawn(move || {
+///         let mut enc = conf.init_write_owned(Vec::new()).unwrap();
+///         enc.process(&[&[0; 1024]]).unwrap();
+///         enc.into_inner().unwrap()
+///     })
+///     .join()
+///     .unwrap();
+/// assert_eq!(&out[..4], b"fLaC");
+/// ```
 #[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
 pub struct FlacEncoderConfig(pub(super) StreamEncoderContainer);
@@ -50,16 +94,44 @@ impl FlacEncoderConfig {
     /// The call to `init_write()` currently will also
     /// immediately write several times, once with the `fLaC`
     /// signature, and once for each encoded metadata block.
-    pub fn init_write<'out>(self, out: &'out mut WriteWrapper<'out>) -> Result<FlacEncoder<'out>, FlacEncoderInitError> {
+    pub fn init_write<'out>(mut self, out: &'out mut WriteWrapper<'out>) -> Result<FlacEncoder<'out>, (FlacEncoderInitError, FlacEncoderConfig)> {
+        if let Err(err) = self.prepare_init() {
+            return Err((err, self));
+        }
+        let mut data = Box::new(EncoderCallbackData { write: Some(NonNull::from(out).cast()), ..EncoderCallbackData::default() });
         let result = unsafe {
             FLAC__stream_encoder_init_stream((self.0).0,
                                              Some(flac_encoder_write_write_callback),
                                              None,
                                              None,
                                              None,
-                                             out as *mut WriteWrapper as *mut c_void)
+                                             &mut *data as *mut EncoderCallbackData as *mut c_void)
         };
-        self.do_init(result)
+        self.do_init(result, data)
+    }
+
+    /// Initialize the encoder instance to encode native FLAC streams into a seekable sink.
+    ///
+    /// Like [`init_write()`](#method.init_write), but when the encoder is finished,
+    /// it will seek back to the STREAMINFO block to write the MD5 signature,
+    /// total sample count and min/max frame sizes collected while encoding.
+    ///
+    /// The stream is expected to be positioned at the start of the FLAC data,
+    /// which need not be the start of the sink.
+    pub fn init_write_seek<'out>(mut self, out: &'out mut WriteSeekWrapper<'out>) -> Result<FlacEncoder<'out>, (FlacEncoderInitError, FlacEncoderConfig)> {
+        if let Err(err) = self.prepare_init() {
+            return Err((err, self));
+        }
+        let mut data = Box::new(EncoderCallbackData { write_seek: Some(NonNull::from(out).cast()), ..EncoderCallbackData::default() });
+        let result = unsafe {
+            FLAC__stream_encoder_init_stream((self.0).0,
+                                             Some(flac_encoder_write_seek_write_callback),
+                                             Some(flac_encoder_write_seek_seek_callback),
+                                             Some(flac_encoder_write_seek_tell_callback),
+                                             None,
+                                             &mut *data as *mut EncoderCallbackData as *mut c_void)
+        };
+        self.do_init(result, data)
     }
 
     /// Initialize the encoder instance to encode Ogg FLAC streams.
@@ -70,7 +142,12 @@ impl FlacEncoderConfig {
     /// The call to `init_write_ogg()` currently will also
     /// immediately write several times, once for the Ogg container,
     /// `fLaC` signature, and encoded metadata block.
-    pub fn init_write_ogg<'out>(self, out: &'out mut WriteWrapper<'out>) -> Result<FlacEncoder<'out>, FlacEncoderInitError> {
+    #[cfg(not(feature = "libflac-noogg"))]
+    pub fn init_write_ogg<'out>(mut self, out: &'out mut WriteWrapper<'out>) -> Result<FlacEncoder<'out>, (FlacEncoderInitError, FlacEncoderConfig)> {
+        if let Err(err) = self.prepare_init() {
+            return Err((err, self));
+        }
+        let mut data = Box::new(EncoderCallbackData { write: Some(NonNull::from(out).cast()), ..EncoderCallbackData::default() });
         let result = unsafe {
             FLAC__stream_encoder_init_ogg_stream((self.0).0,
                                                  None,
@@ -78,9 +155,9 @@ impl FlacEncoderConfig {
                                                  None,
                                                  None,
                                                  None,
-                                                 out as *mut WriteWrapper as *mut c_void)
+                                                 &mut *data as *mut EncoderCallbackData as *mut c_void)
         };
-        self.do_init(result)
+        self.do_init(result, data)
     }
 
     /// Initialize the encoder instance to encode native FLAC files.
@@ -91,15 +168,75 @@ impl FlacEncoderConfig {
     /// and provide the output stream.
     ///
     /// The file will be opened with `fopen()`.
-    pub fn init_file<P: AsRef<Path>>(self, filename: &P /* FLAC__StreamEncoderProgressCallback progress_callback, void *client_data */)
-                                     -> Result<FlacEncoder<'static>, FlacEncoderInitError> {
-        self.init_file_impl(filename.as_ref())
+    ///
+    /// The filename is passed as-is, except on Windows, where it must be valid Unicode;
+    /// a filename that can't be represented, or contains a NUL, fails with
+    /// [`InvalidFilename`](enum.FlacEncoderInitError.html#variant.InvalidFilename).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use flac_bound::{FlacEncoderInitError, FlacEncoder};
+    /// let (err, conf) = FlacEncoder::new().unwrap().init_file(&"ЦшЦ\0.flac").unwrap_err();
+    /// assert_eq!(err, FlacEncoderInitError::InvalidFilename);
+    ///
+    /// # #[cfg(unix)] {
+    /// # use std::os::unix::ffi::OsStrExt;
+    /// # use std::ffi::OsStr;
+    /// // Latin-1 "été.flac"
+    /// conf.init_file(&OsStr::from_bytes(b"\xE9t\xE9.flac")).unwrap().finish().unwrap();
+    /// # std::fs::remove_file(OsStr::from_bytes(b"\xE9t\xE9.flac")).unwrap();
+    /// # }
+    /// ```
+    pub fn init_file<P: AsRef<Path>>(self, filename: &P) -> Result<FlacEncoder<'static>, (FlacEncoderInitError, FlacEncoderConfig)> {
+        self.init_file_impl(filename.as_ref(), Box::default())
     }
 
-    fn init_file_impl(self, filename: &Path /* FLAC__StreamEncoderProgressCallback progress_callback, void *client_data */)
-                      -> Result<FlacEncoder<'static>, FlacEncoderInitError> {
-        let result = unsafe { FLAC__stream_encoder_init_file((self.0).0, FlacEncoderConfig::convert_path(filename).as_ptr(), None, ptr::null_mut()) };
-        self.do_init(result)
+    /// Initialize the encoder instance to encode native FLAC files, reporting progress.
+    ///
+    /// Like [`init_file()`](#method.init_file), but `progress` will be called after each frame is written with
+    /// `(bytes_written, samples_written, frames_written, total_frames_estimate)`.
+    ///
+    /// `total_frames_estimate` is only meaningful if [`total_samples_estimate()`](#method.total_samples_estimate) was set,
+    /// and is `0` otherwise.
+    ///
+    /// If `progress` panics, it won't be called again, and the panic will be resumed
+    /// once control returns from the [`FlacEncoder`](struct.FlacEncoder.html) call that triggered it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use flac_bound::FlacEncoder;
+    /// let mut frames = 0;
+    /// let mut enc = FlacEncoder::new().unwrap()
+    ///     .channels(1)
+    ///     .init_file_progress(&"ЦшЦ-progress.flac", |_, _, frames_written, _| frames = frames_written)
+    ///     .unwrap();
+    ///
+    /// enc.process(&[&[0; 10000]]).unwrap();
+    /// enc.finish().unwrap();
+    /// assert!(frames > 0);
+    /// ```
+    pub fn init_file_progress<'out, P: AsRef<Path>, F: FnMut(u64, u64, u32, u32) + 'out>(self, filename: &P, progress: F)
+                                                                                        -> Result<FlacEncoder<'out>, (FlacEncoderInitError, FlacEncoderConfig)> {
+        self.init_file_impl(filename.as_ref(), FlacEncoderConfig::progress_data(progress))
+    }
+
+    fn init_file_impl<'out>(mut self, filename: &Path, mut data: Box<EncoderCallbackData<'out>>) -> Result<FlacEncoder<'out>, (FlacEncoderInitError, FlacEncoderConfig)> {
+        let filename = match util::convert_path(filename) {
+            Some(filename) => filename,
+            None => return Err((FlacEncoderInitError::InvalidFilename, self)),
+        };
+        if let Err(err) = self.prepare_init() {
+            return Err((err, self));
+        }
+        let result = unsafe {
+            FLAC__stream_encoder_init_file((self.0).0,
+                                           filename.as_ptr(),
+                                           FlacEncoderConfig::progress_callback(&data),
+                                           &mut *data as *mut EncoderCallbackData as *mut c_void)
+        };
+        self.do_init(result, data)
     }
 
     /// Initialize the encoder instance to encode Ogg FLAC files.
@@ -110,15 +247,37 @@ impl FlacEncoderConfig {
     /// and provide the output stream.
     ///
     /// The file will be opened with `fopen()`.
-    pub fn init_file_ogg<P: AsRef<Path>>(self, filename: &P /* FLAC__StreamEncoderProgressCallback progress_callback, void *client_data */)
-                                         -> Result<FlacEncoder<'static>, FlacEncoderInitError> {
-        self.init_file_ogg_impl(filename.as_ref())
+    #[cfg(not(feature = "libflac-noogg"))]
+    pub fn init_file_ogg<P: AsRef<Path>>(self, filename: &P) -> Result<FlacEncoder<'static>, (FlacEncoderInitError, FlacEncoderConfig)> {
+        self.init_file_ogg_impl(filename.as_ref(), Box::default())
     }
 
-    fn init_file_ogg_impl(self, filename: &Path /* FLAC__StreamEncoderProgressCallback progress_callback, void *client_data */)
-                          -> Result<FlacEncoder<'static>, FlacEncoderInitError> {
-        let result = unsafe { FLAC__stream_encoder_init_ogg_file((self.0).0, FlacEncoderConfig::convert_path(filename).as_ptr(), None, ptr::null_mut()) };
-        self.do_init(result)
+    /// Initialize the encoder instance to encode Ogg FLAC files, reporting progress.
+    ///
+    /// Like [`init_file_ogg()`](#method.init_file_ogg), but `progress` is called like in
+    /// [`init_file_progress()`](#method.init_file_progress).
+    #[cfg(not(feature = "libflac-noogg"))]
+    pub fn init_file_ogg_progress<'out, P: AsRef<Path>, F: FnMut(u64, u64, u32, u32) + 'out>(self, filename: &P, progress: F)
+                                                                                            -> Result<FlacEncoder<'out>, (FlacEncoderInitError, FlacEncoderConfig)> {
+        self.init_file_ogg_impl(filename.as_ref(), FlacEncoderConfig::progress_data(progress))
+    }
+
+    #[cfg(not(feature = "libflac-noogg"))]
+    fn init_file_ogg_impl<'out>(mut self, filename: &Path, mut data: Box<EncoderCallbackData<'out>>) -> Result<FlacEncoder<'out>, (FlacEncoderInitError, FlacEncoderConfig)> {
+        let filename = match util::convert_path(filename) {
+            Some(filename) => filename,
+            None => return Err((FlacEncoderInitError::InvalidFilename, self)),
+        };
+        if let Err(err) = self.prepare_init() {
+            return Err((err, self));
+        }
+        let result = unsafe {
+            FLAC__stream_encoder_init_ogg_file((self.0).0,
+                                               filename.as_ptr(),
+                                               FlacEncoderConfig::progress_callback(&data),
+                                               &mut *data as *mut EncoderCallbackData as *mut c_void)
+        };
+        self.do_init(result, data)
     }
 
     /// Initialize the encoder instance to encode native FLAC files.
@@ -127,9 +286,12 @@ impl FlacEncoderConfig {
     /// FLAC file to stdout.
     ///
     /// **Note**: a proper SEEKTABLE cannot be created when encoding to `stdout` since it is not seekable.
-    pub fn init_stdout(self) -> Result<FlacEncoder<'static>, FlacEncoderInitError> {
+    pub fn init_stdout(mut self) -> Result<FlacEncoder<'static>, (FlacEncoderInitError, FlacEncoderConfig)> {
+        if let Err(err) = self.prepare_init() {
+            return Err((err, self));
+        }
         let result = unsafe { FLAC__stream_encoder_init_file((self.0).0, ptr::null(), None, ptr::null_mut()) };
-        self.do_init(result)
+        self.do_init(result, Box::default())
     }
 
     /// Initialize the encoder instance to encode Ogg FLAC files.
@@ -138,20 +300,145 @@ impl FlacEncoderConfig {
     /// OGG FLAC file to stdout.
     ///
     /// **Note**: a proper SEEKTABLE cannot be created when encoding to `stdout` since it is not seekable.
-    pub fn init_stdout_ogg(self) -> Result<FlacEncoder<'static>, FlacEncoderInitError> {
+    #[cfg(not(feature = "libflac-noogg"))]
+    pub fn init_stdout_ogg(mut self) -> Result<FlacEncoder<'static>, (FlacEncoderInitError, FlacEncoderConfig)> {
+        if let Err(err) = self.prepare_init() {
+            return Err((err, self));
+        }
         let result = unsafe { FLAC__stream_encoder_init_ogg_file((self.0).0, ptr::null(), None, ptr::null_mut()) };
-        self.do_init(result)
+        self.do_init(result, Box::default())
     }
 
-    fn convert_path(path: &Path) -> CString {
-        CString::new(path.to_str().expect("non-UTF-8 filename")).expect("filename has internal NULs")
+    /// Get the names of the settings libFLAC rejected, i.e. the builder functions whose
+    /// `FLAC__stream_encoder_set_*()` call returned `false`, in the order they were called.
+    ///
+    /// Setting one successfully again removes it from this list.
+    /// If any are listed, initialisation fails with
+    /// [`SettingRejected`](enum.FlacEncoderInitError.html#variant.SettingRejected), and
+    /// [`validate()`](#method.validate) reports them.
+    ///
+    /// Every setter is checked; the metadata blocks are reported as `"metadata"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use flac_bound::FlacEncoder;
+    /// let conf = FlacEncoder::new().unwrap().channels(1).compression_level(8);
+    /// assert_eq!(conf.rejected_settings(), &[] as &[&str]);
+    /// assert_eq!(conf.validate(), Ok(()));
+    /// ```
+    pub fn rejected_settings(&self) -> &[&'static str] {
+        &(self.0).3
     }
 
-    fn do_init<'out>(self, init_result: FLAC__StreamEncoderInitStatus) -> Result<FlacEncoder<'out>, FlacEncoderInitError> {
+    /// Check the current settings against the format limits and, if
+    /// [`streamable_subset()`](#method.streamable_subset) is set, the [Subset](https://xiph.org/flac/format.html#subset),
+    /// the same way [`init_*()`](#method.init_write) does, but returning every violation, with the offending values.
+    ///
+    /// An unset [`blocksize()`](#method.blocksize) is checked as the one the encoder will pick.
+    ///
+    /// Metadata isn't checked here; see [`CueSheet::validate()`](struct.CueSheet.html#method.validate) and
+    /// [`Picture::validate()`](struct.Picture.html#method.validate).
+    pub fn validate(&self) -> Result<(), Vec<FlacEncoderConfigError>> {
+        let mut ret: Vec<_> = (self.0).3.iter().map(|&setting| FlacEncoderConfigError::SettingRejected(setting)).collect();
+
+        let channels = self.get_channels();
+        if channels == 0 || channels > FLAC__MAX_CHANNELS {
+            ret.push(FlacEncoderConfigError::ChannelCount(channels));
+        }
+
+        let bits_per_sample = self.get_bits_per_sample();
+        if !(FLAC__MIN_BITS_PER_SAMPLE..=FLAC__REFERENCE_CODEC_MAX_BITS_PER_SAMPLE).contains(&bits_per_sample) {
+            ret.push(FlacEncoderConfigError::BitsPerSample(bits_per_sample));
+        }
+
+        let sample_rate = self.get_sample_rate();
+        if unsafe { FLAC__format_sample_rate_is_valid(sample_rate) } == 0 {
+            ret.push(FlacEncoderConfigError::SampleRate(sample_rate));
+        }
+
+        let max_lpc_order = self.get_max_lpc_order();
+        let blocksize = match self.get_blocksize() {
+            0 if max_lpc_order == 0 => 1152,
+            0 => 4096,
+            blocksize => blocksize,
+        };
+        if !(FLAC__MIN_BLOCK_SIZE..=FLAC__MAX_BLOCK_SIZE).contains(&blocksize) {
+            ret.push(FlacEncoderConfigError::BlockSize(blocksize));
+        }
+
+        if max_lpc_order > FLAC__MAX_LPC_ORDER {
+            ret.push(FlacEncoderConfigError::MaxLpcOrder(max_lpc_order));
+        }
+        if blocksize < max_lpc_order {
+            ret.push(FlacEncoderConfigError::BlockSizeTooSmallForLpcOrder { blocksize, max_lpc_order });
+        }
+
+        let qlp_coeff_precision = self.get_qlp_coeff_precision();
+        if qlp_coeff_precision != 0 && !(FLAC__MIN_QLP_COEFF_PRECISION..=FLAC__MAX_QLP_COEFF_PRECISION).contains(&qlp_coeff_precision) {
+            ret.push(FlacEncoderConfigError::QlpCoeffPrecision(qlp_coeff_precision));
+        }
+        #[cfg(feature = "flac")]
+        {
+            if qlp_coeff_precision != 0 && qlp_coeff_precision + bits_per_sample >= 32 {
+                ret.push(FlacEncoderConfigError::QlpCoeffPrecisionTooHighForBitsPerSample { qlp_coeff_precision, bits_per_sample });
+            }
+        }
+
+        if self.get_streamable_subset() {
+            if unsafe { FLAC__format_sample_rate_is_subset(sample_rate) } == 0 {
+                ret.push(FlacEncoderConfigError::SubsetSampleRate(sample_rate));
+            }
+            if ![8, 12, 16, 20, 24, 32].contains(&bits_per_sample) {
+                ret.push(FlacEncoderConfigError::SubsetBitsPerSample(bits_per_sample));
+            }
+            if unsafe { FLAC__format_blocksize_is_subset(blocksize, sample_rate) } == 0 {
+                ret.push(FlacEncoderConfigError::SubsetBlockSize { blocksize, sample_rate });
+            }
+            if sample_rate <= 48000 && max_lpc_order > FLAC__SUBSET_MAX_LPC_ORDER_48000HZ {
+                ret.push(FlacEncoderConfigError::SubsetMaxLpcOrder { max_lpc_order, sample_rate });
+            }
+            let max_residual_partition_order = self.get_max_residual_partition_order();
+            if max_residual_partition_order > FLAC__SUBSET_MAX_RICE_PARTITION_ORDER {
+                ret.push(FlacEncoderConfigError::SubsetMaxResidualPartitionOrder(max_residual_partition_order));
+            }
+        }
+
+        if ret.is_empty() { Ok(()) } else { Err(ret) }
+    }
+
+    fn progress_data<'out, F: FnMut(u64, u64, u32, u32) + 'out>(progress: F) -> Box<EncoderCallbackData<'out>> {
+        Box::new(EncoderCallbackData { progress: Some(Box::new(progress)), ..EncoderCallbackData::default() })
+    }
+
+    fn progress_callback(data: &EncoderCallbackData) -> FLAC__StreamEncoderProgressCallback {
+        data.progress.as_ref().map(|_| flac_encoder_progress_callback as _)
+    }
+
+    /// Turn the result of a libFLAC init call into the encoder, or the error and the config to retry with.
+    ///
+    /// A sink panicking while writing the header fails initialisation, and the panic is resumed once the encoder is reset.
+    fn do_init<'out>(mut self, init_result: FLAC__StreamEncoderInitStatus, mut data: Box<EncoderCallbackData<'out>>)
+                     -> Result<FlacEncoder<'out>, (FlacEncoderInitError, FlacEncoderConfig)> {
         if init_result == FLAC__StreamEncoderInitStatus_FLAC__STREAM_ENCODER_INIT_STATUS_OK {
-            Ok(FlacEncoder(self.0, PhantomData))
+            Ok(FlacEncoder(self.0, PhantomData, data))
         } else {
-            Err(FlacEncoderInitError::try_from(init_result).unwrap())
+            // If initialisation got far enough to change the state (e.g. the file couldn't be opened),
+            // libFLAC refuses any further settings until the encoder is reset, which also resets them to their defaults
+            if unsafe { FLAC__stream_encoder_get_state((self.0).0) } != FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_UNINITIALIZED {
+                let settings = EncoderSettings::from_config(&self);
+                let compression_level = mem::take(&mut (self.0).4).compression_level;
+                unsafe { FLAC__stream_encoder_finish((self.0).0) };
+
+                // Everything but the apodization the compression level implies is overridden by the settings
+                if let Some(level) = compression_level {
+                    self = self.compression_level(level);
+                }
+                self = settings.apply(self);
+                self.update_metadata();
+            }
+            data.resume_panic();
+            Err((FlacEncoderInitError::try_from(init_result).unwrap(), self))
         }
     }
 }
@@ -167,9 +454,13 @@ impl FlacEncoderConfig {
     /// may collide with other streams.
     ///
     /// **Default**: `0`
-    pub fn ogg_serial_number(self, serial_number: c_long) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_ogg_serial_number((self.0).0, serial_number) };
-        self
+    #[cfg(not(feature = "libflac-noogg"))]
+    pub fn ogg_serial_number(mut self, serial_number: c_long) -> FlacEncoderConfig {
+        let result = unsafe { FLAC__stream_encoder_set_ogg_serial_number((self.0).0, serial_number) };
+        if result != 0 {
+            (self.0).4.ogg_serial_number = Some(serial_number);
+        }
+        self.checked("ogg_serial_number", result)
     }
 
     /// Set the "verify" flag.
@@ -182,8 +473,8 @@ impl FlacEncoderConfig {
     ///
     /// **Default**: `false`
     pub fn verify(self, value: bool) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_verify((self.0).0, value as FLAC__bool) };
-        self
+        let result = unsafe { FLAC__stream_encoder_set_verify((self.0).0, value as FLAC__bool) };
+        self.checked("verify", result)
     }
 
     /// Set the Subset flag.
@@ -197,16 +488,16 @@ impl FlacEncoderConfig {
     ///
     /// **Default**: `true`
     pub fn streamable_subset(self, value: bool) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_streamable_subset((self.0).0, value as FLAC__bool) };
-        self
+        let result = unsafe { FLAC__stream_encoder_set_streamable_subset((self.0).0, value as FLAC__bool) };
+        self.checked("streamable_subset", result)
     }
 
     /// Set the number of channels to be encoded.
     ///
     /// **Default**: `2`
     pub fn channels(self, value: u32) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_channels((self.0).0, value) };
-        self
+        let result = unsafe { FLAC__stream_encoder_set_channels((self.0).0, value) };
+        self.checked("channels", result)
     }
 
     /// Set the sample resolution of the input to be encoded.
@@ -217,16 +508,16 @@ impl FlacEncoderConfig {
     ///
     /// **Default**: `16`
     pub fn bits_per_sample(self, value: u32) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_bits_per_sample((self.0).0, value) };
-        self
+        let result = unsafe { FLAC__stream_encoder_set_bits_per_sample((self.0).0, value) };
+        self.checked("bits_per_sample", result)
     }
 
     /// Set the sample rate (in Hz) of the input to be encoded.
     ///
     /// **Default**: `44100`
     pub fn sample_rate(self, value: u32) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_sample_rate((self.0).0, value) };
-        self
+        let result = unsafe { FLAC__stream_encoder_set_sample_rate((self.0).0, value) };
+        self.checked("sample_rate", result)
     }
 
     /// Set the compression level
@@ -301,9 +592,13 @@ impl FlacEncoderConfig {
     /// </table>
     ///
     /// **Default**: `5`
-    pub fn compression_level(self, value: u32) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_compression_level((self.0).0, value) };
-        self
+    pub fn compression_level(mut self, value: u32) -> FlacEncoderConfig {
+        let result = unsafe { FLAC__stream_encoder_set_compression_level((self.0).0, value) };
+        if result != 0 {
+            (self.0).4.compression_level = Some(value);
+            (self.0).4.apodization = None;
+        }
+        self.checked("compression_level", result)
     }
 
     /// Set the blocksize to use while encoding.
@@ -313,8 +608,8 @@ impl FlacEncoderConfig {
     ///
     /// **Default**: `0`
     pub fn blocksize(self, value: u32) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_blocksize((self.0).0, value) };
-        self
+        let result = unsafe { FLAC__stream_encoder_set_blocksize((self.0).0, value) };
+        self.checked("blocksize", result)
     }
 
     /// Set to `true` to enable mid-side encoding on stereo input.
@@ -324,8 +619,8 @@ impl FlacEncoderConfig {
     ///
     /// **Default**: `true`
     pub fn do_mid_side_stereo(self, value: bool) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_do_mid_side_stereo((self.0).0, value as FLAC__bool) };
-        self
+        let result = unsafe { FLAC__stream_encoder_set_do_mid_side_stereo((self.0).0, value as FLAC__bool) };
+        self.checked("do_mid_side_stereo", result)
     }
 
     /// Set to `true` to enable adaptive switching between mid-side and left-right encoding on stereo input.
@@ -335,8 +630,8 @@ impl FlacEncoderConfig {
     ///
     /// **Default**: `false`
     pub fn loose_mid_side_stereo(self, value: bool) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_loose_mid_side_stereo((self.0).0, value as FLAC__bool) };
-        self
+        let result = unsafe { FLAC__stream_encoder_set_loose_mid_side_stereo((self.0).0, value as FLAC__bool) };
+        self.checked("loose_mid_side_stereo", result)
     }
 
     /// Sets the apodization function(s) the encoder will use when windowing audio data for LPC analysis.
@@ -393,18 +688,23 @@ impl FlacEncoderConfig {
     /// values of P, STDDEV and ov are locale-specific, so if the comma
     /// separator specified by the locale is a comma, a comma should be used.
     ///
+    /// Use [`Apodization::specification()`](enum.Apodization.html#method.specification) to build a validated specification.
+    ///
     /// **Default**: `"tukey(0.5)"`
-    pub fn apodization(self, specification: &CStr) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_apodization((self.0).0, specification.as_ptr()) };
-        self
+    pub fn apodization(mut self, specification: &CStr) -> FlacEncoderConfig {
+        let result = unsafe { FLAC__stream_encoder_set_apodization((self.0).0, specification.as_ptr()) };
+        if result != 0 {
+            (self.0).4.apodization = Some(specification.to_owned());
+        }
+        self.checked("apodization", result)
     }
 
     /// Set the maximum LPC order, or `0` to use only the fixed predictors.
     ///
     /// **Default**: `8`
     pub fn max_lpc_order(self, value: u32) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_max_lpc_order((self.0).0, value) };
-        self
+        let result = unsafe { FLAC__stream_encoder_set_max_lpc_order((self.0).0, value) };
+        self.checked("max_lpc_order", result)
     }
 
     /// Set the precision, in bits, of the quantized linear predictor
@@ -417,8 +717,8 @@ impl FlacEncoderConfig {
     ///
     /// **Default**: `0`
     pub fn qlp_coeff_precision(self, value: u32) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_qlp_coeff_precision((self.0).0, value) };
-        self
+        let result = unsafe { FLAC__stream_encoder_set_qlp_coeff_precision((self.0).0, value) };
+        self.checked("qlp_coeff_precision", result)
     }
 
     /// Set to `false` to use only the specified quantized linear predictor
@@ -427,16 +727,16 @@ impl FlacEncoderConfig {
     ///
     /// **Default**: `false`
     pub fn do_qlp_coeff_prec_search(self, value: bool) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_do_qlp_coeff_prec_search((self.0).0, value as FLAC__bool) };
-        self
+        let result = unsafe { FLAC__stream_encoder_set_do_qlp_coeff_prec_search((self.0).0, value as FLAC__bool) };
+        self.checked("do_qlp_coeff_prec_search", result)
     }
 
     /// Deprecated. Setting this value has no effect.
     ///
     /// **Default**: `false`
     pub fn do_escape_coding(self, value: bool) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_do_escape_coding((self.0).0, value as FLAC__bool) };
-        self
+        let result = unsafe { FLAC__stream_encoder_set_do_escape_coding((self.0).0, value as FLAC__bool) };
+        self.checked("do_escape_coding", result)
     }
 
     /// Set to `false` to let the encoder estimate the best model order
@@ -445,8 +745,8 @@ impl FlacEncoderConfig {
     ///
     /// **Default**: `false`
     pub fn do_exhaustive_model_search(self, value: bool) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_do_exhaustive_model_search((self.0).0, value as FLAC__bool) };
-        self
+        let result = unsafe { FLAC__stream_encoder_set_do_exhaustive_model_search((self.0).0, value as FLAC__bool) };
+        self.checked("do_exhaustive_model_search", result)
     }
 
     /// Set the minimum partition order to search when coding the residual.
@@ -465,8 +765,8 @@ impl FlacEncoderConfig {
     ///
     /// **Default**: `0`
     pub fn min_residual_partition_order(self, value: u32) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_min_residual_partition_order((self.0).0, value) };
-        self
+        let result = unsafe { FLAC__stream_encoder_set_min_residual_partition_order((self.0).0, value) };
+        self.checked("min_residual_partition_order", result)
     }
 
     /// Set the maximum partition order to search when coding the residual.
@@ -484,8 +784,8 @@ impl FlacEncoderConfig {
     ///
     /// **Default**: `5`
     pub fn max_residual_partition_order(self, value: u32) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_max_residual_partition_order((self.0).0, value) };
-        self
+        let result = unsafe { FLAC__stream_encoder_set_max_residual_partition_order((self.0).0, value) };
+        self.checked("max_residual_partition_order", result)
     }
 
     /// Limit the compression of digital silence to prevent streaming connection loss
@@ -495,18 +795,181 @@ impl FlacEncoderConfig {
     /// **Default**: `false`
     #[cfg(feature = "libflac-nobuild")]
     pub fn set_limit_min_bitrate(self, value: bool) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_limit_min_bitrate((self.0).0, value as FLAC__bool) };
-        self
+        let result = unsafe { FLAC__stream_encoder_set_limit_min_bitrate((self.0).0, value as FLAC__bool) };
+        self.checked("set_limit_min_bitrate", result)
     }
 
     /// Deprecated. Setting this value has no effect.
     ///
     /// **Default**: `0`
     pub fn rice_parameter_search_dist(self, value: u32) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_rice_parameter_search_dist((self.0).0, value) };
+        let result = unsafe { FLAC__stream_encoder_set_rice_parameter_search_dist((self.0).0, value) };
+        self.checked("rice_parameter_search_dist", result)
+    }
+
+    /// Set the metadata blocks to be emitted to the stream before encoding, replacing any set previously.
+    ///
+    /// The STREAMINFO block is always written and cannot be specified here.
+    /// The blocks will be written in the specified order, except for Ogg FLAC,
+    /// where a VORBIS_COMMENT block, if present, will be moved to the front,
+    /// as mandated by the [Ogg FLAC mapping](https://xiph.org/flac/ogg_mapping.html).
+    ///
+    /// The blocks are owned by the encoder for as long as it needs them, and released when
+    /// [`FlacEncoder::finish()`](struct.FlacEncoder.html#method.finish) resets the settings, or when the encoder is dropped.
+    ///
+    /// If SEEKTABLE or PADDING blocks are supplied, the encoder will fill them in or resize them as needed
+    /// (see the [libFLAC documentation](https://xiph.org/flac/api/group__flac__stream__encoder.html)).
+    ///
+    /// **Default**: none
+    pub fn metadata<I: IntoIterator<Item = FlacMetadata>>(mut self, blocks: I) -> FlacEncoderConfig {
+        let _old = mem::replace(&mut (self.0).1, blocks.into_iter().collect());
+        self.update_metadata();
+        self
+    }
+
+    /// Append a metadata block to the ones to be emitted to the stream before encoding.
+    ///
+    /// See [`metadata()`](#method.metadata) for details.
+    pub fn metadata_block(mut self, block: FlacMetadata) -> FlacEncoderConfig {
+        (self.0).1.push(block);
+        self.update_metadata();
+        self
+    }
+
+    /// Set the tags to be written to the stream, replacing any VORBIS_COMMENT block set previously.
+    ///
+    /// See [`metadata()`](#method.metadata) for details.
+    pub fn vorbis_comment(mut self, comment: &VorbisComment) -> FlacEncoderConfig {
+        match comment.to_metadata() {
+            Some(block) => {
+                self.replace_metadata_block(block);
+                self.checked("vorbis_comment", 1)
+            }
+            None => self.checked("vorbis_comment", 0),
+        }
+    }
+
+    /// Append a picture, e.g. cover art, to be written to the stream.
+    ///
+    /// Any number of PICTURE blocks may be added, but only one each of
+    /// [`FileIconStandard`](enum.PictureType.html#variant.FileIconStandard) and
+    /// [`FileIcon`](enum.PictureType.html#variant.FileIcon) is permitted by the format.
+    ///
+    /// See [`metadata()`](#method.metadata) for details.
+    pub fn picture(self, picture: &Picture) -> FlacEncoderConfig {
+        match picture.to_metadata() {
+            // Not cleared on success, since pictures are appended, so an earlier one is still missing
+            Some(block) => self.metadata_block(block),
+            None => self.checked("picture", 0),
+        }
+    }
+
+    /// Set the cue sheet to be written to the stream, replacing any CUESHEET block set previously.
+    ///
+    /// An illegal cue sheet will make initialisation fail with
+    /// [`InvalidMetadata`](enum.FlacEncoderInitError.html#variant.InvalidMetadata);
+    /// use [`CueSheet::validate()`](struct.CueSheet.html#method.validate) to find out why.
+    ///
+    /// See [`metadata()`](#method.metadata) for details.
+    pub fn cue_sheet(mut self, sheet: &CueSheet) -> FlacEncoderConfig {
+        match sheet.to_metadata() {
+            Some(block) => {
+                self.replace_metadata_block(block);
+                self.checked("cue_sheet", 1)
+            }
+            None => self.checked("cue_sheet", 0),
+        }
+    }
+
+    /// Generate a SEEKTABLE block from the specified template when the encoder is initialised,
+    /// replacing any SEEKTABLE block set previously.
+    ///
+    /// The template is resolved against the [`sample_rate()`](#method.sample_rate) and
+    /// [`total_samples_estimate()`](#method.total_samples_estimate) in effect at initialisation,
+    /// so these may be set in any order. If the template yields no points, no block is written.
+    ///
+    /// See [`SeekTableTemplate`](enum.SeekTableTemplate.html) and [`metadata()`](#method.metadata) for details.
+    ///
+    /// [`SeekTableTemplate::EverySeconds`](enum.SeekTableTemplate.html#variant.EverySeconds) with a negative or non-finite interval
+    /// is rejected.
+    ///
+    /// **Default**: none
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use flac_bound::{FlacEncoder, SeekTableTemplate};
+    /// # use std::f64;
+    /// let conf = FlacEncoder::new().unwrap().seek_table(SeekTableTemplate::EverySeconds(f64::NAN));
+    /// assert_eq!(conf.rejected_settings(), ["seek_table"]);
+    ///
+    /// let conf = conf.seek_table(SeekTableTemplate::EverySeconds(10.0));
+    /// assert_eq!(conf.rejected_settings(), &[] as &[&str]);
+    /// ```
+    pub fn seek_table(mut self, template: SeekTableTemplate) -> FlacEncoderConfig {
+        let valid = match template {
+            SeekTableTemplate::EverySeconds(seconds) => seconds.is_finite() && seconds >= 0.0,
+            _ => true,
+        };
+        (self.0).2 = Some(template);
+        self.checked("seek_table", valid as FLAC__bool)
+    }
+
+    /// Replace the first block of the same type as `block` or append it if there is none.
+    fn replace_metadata_block(&mut self, block: FlacMetadata) {
+        let tp = block.metadata_type();
+        match (self.0).1.iter().position(|b| b.metadata_type() == tp) {
+            Some(idx) => {
+                let _old = mem::replace(&mut (self.0).1[idx], block);
+            }
+            None => (self.0).1.push(block),
+        }
+        self.update_metadata();
+    }
+
+    /// Resolve the seek table and make sure no settings were rejected, right before initialisation.
+    fn prepare_init(&mut self) -> Result<(), FlacEncoderInitError> {
+        self.resolve_seek_table()?;
+        if (self.0).3.is_empty() {
+            Ok(())
+        } else {
+            Err(FlacEncoderInitError::SettingRejected)
+        }
+    }
+
+    /// Turn the seek table template, if any, into a SEEKTABLE block.
+    fn resolve_seek_table(&mut self) -> Result<(), FlacEncoderInitError> {
+        let block = match (self.0).2.as_ref() {
+            Some(template) => {
+                template.to_metadata(self.get_sample_rate(), self.get_total_samples_estimate()).ok_or(FlacEncoderInitError::MemoryAllocationError)?
+            }
+            None => return Ok(()),
+        };
+        if block.length() != 0 {
+            self.replace_metadata_block(block);
+        }
+        Ok(())
+    }
+
+    fn update_metadata(&mut self) {
+        let mut blocks: Vec<_> = (self.0).1.iter().map(|b| b.0).collect();
+        let result = unsafe { FLAC__stream_encoder_set_metadata((self.0).0, blocks.as_mut_ptr(), blocks.len() as u32) };
+        self.check_setting("metadata", result);
+    }
+
+    fn checked(mut self, setting: &'static str, result: FLAC__bool) -> FlacEncoderConfig {
+        self.check_setting(setting, result);
         self
     }
 
+    /// Remember if libFLAC rejected the setting, until it's set successfully, to fail initialisation with.
+    fn check_setting(&mut self, setting: &'static str, result: FLAC__bool) {
+        (self.0).3.retain(|&s| s != setting);
+        if result == 0 {
+            (self.0).3.push(setting);
+        }
+    }
+
     /// Set an estimate of the total samples that will be encoded.
     ///
     /// This is merely an estimate and may be set to `0` if unknown.
@@ -516,7 +979,7 @@ impl FlacEncoderConfig {
     ///
     /// **Default**: `0`
     pub fn total_samples_estimate(self, value: u64) -> FlacEncoderConfig {
-        unsafe { FLAC__stream_encoder_set_total_samples_estimate((self.0).0, value) };
-        self
+        let result = unsafe { FLAC__stream_encoder_set_total_samples_estimate((self.0).0, value) };
+        self.checked("total_samples_estimate", result)
     }
 }
@@ -1,20 +1,26 @@
 #[cfg(feature = "flac")]
 use flac_sys::{FLAC__stream_encoder_new, FLAC__stream_encoder_get_state, FLAC__stream_encoder_get_verify_decoder_state, FLAC__stream_encoder_finish,
-               FLAC__stream_encoder_process, FLAC__stream_encoder_process_interleaved};
+               FLAC__stream_encoder_process, FLAC__stream_encoder_process_interleaved, FLAC__stream_encoder_get_verify_decoder_error_stats,
+               FLAC__stream_encoder_get_resolved_state_string};
 
 #[cfg(feature = "libflac-nobuild")]
 use libflac_sys::{FLAC__stream_encoder_new, FLAC__stream_encoder_get_state, FLAC__stream_encoder_get_verify_decoder_state, FLAC__stream_encoder_finish,
-    FLAC__stream_encoder_process, FLAC__stream_encoder_process_interleaved};
+    FLAC__stream_encoder_process, FLAC__stream_encoder_process_interleaved, FLAC__stream_encoder_get_verify_decoder_error_stats,
+    FLAC__stream_encoder_get_resolved_state_string};
 
-use super::{StreamEncoderContainer, FlacEncoderConfig, FlacEncoderState};
+use super::{StreamEncoderContainer, EncoderCallbackData, FlacEncoderConfig, FlacEncoderInputError, FlacEncoderError, FlacEncoderState, VerifyMismatch};
+use decoder::FlacDecoderState;
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
+use std::cmp::Ordering;
 use std::convert::TryFrom;
+use std::ffi::CStr;
 use std::os::raw::c_uint;
 use std::{mem, ptr};
 
 
 /// The [stream encoder](https://xiph.org/flac/api/group__flac__stream__encoder.html) can encode to native FLAC,
-/// and optionally Ogg FLAC (check FLAC_API_SUPPORTS_OGG_FLAC) streams and files.
+/// and Ogg FLAC (unless the `"libflac-noogg"` feature is on) streams and files.
 ///
 /// The basic usage of this encoder is as follows:
 ///   * The program creates an instance of an encoder using
@@ -40,6 +46,7 @@ use std::{mem, ptr};
 ///   * The program initializes the instance to validate the settings and
 ///     prepare for encoding using
 ///       * [`FlacEncoderConfig::init_write()`](struct.FlacEncoderConfig.html#method.init_write), or
+///         [`FlacEncoderConfig::init_write_seek()`](struct.FlacEncoderConfig.html#method.init_write_seek), or
 ///         [`FlacEncoderConfig::init_file()`](struct.FlacEncoderConfig.html#method.init_file), or
 ///         [`FlacEncoderConfig::init_stdout()`](struct.FlacEncoderConfig.html#method.init_stdout) for native FLAC
 ///       * [`FlacEncoderConfig::init_write_ogg()`](struct.FlacEncoderConfig.html#method.init_write_ogg), or
@@ -78,22 +85,31 @@ use std::{mem, ptr};
 /// before [`FlacEncoderConfig::init_*()`](struct.FlacEncoderConfig.html#method.init_write) will take on the defaults from
 /// the constructor.
 ///
-/// There are three initialization functions for native FLAC, one for
+/// There are four initialization functions for native FLAC, two for
 /// setting up the encoder to encode FLAC data to the client via
 /// a `Write` stream, and two for encoding directly to a file.
 ///
 /// For encoding via a `Write` stream, use [`FlacEncoderConfig::init_write()`](struct.FlacEncoderConfig.html#method.init_write).
 /// You must also supply a `std::io::Write` stream which will be called anytime
-/// there is raw encoded data to write. The client cannot seek the output due to
-/// [RFC 2035](https://github.com/rust-lang/rfcs/issues/2035), so the
+/// there is raw encoded data to write. The output cannot be seeked, so the
 /// encoder cannot go back after encoding is finished to write back
 /// information that was collected while encoding, like seek point offsets,
 /// frame sizes, etc.
 ///
+/// If the output is also `std::io::Seek`, use [`FlacEncoderConfig::init_write_seek()`](struct.FlacEncoderConfig.html#method.init_write_seek)
+/// instead, which will let the encoder do just that. Due to
+/// [RFC 2035](https://github.com/rust-lang/rfcs/issues/2035), the stream is supplied through a separate
+/// [`WriteSeekWrapper`](struct.WriteSeekWrapper.html).
+///
+/// To have the encoder own the stream instead of borrowing it, use
+/// [`FlacEncoderConfig::init_write_owned()`](struct.FlacEncoderConfig.html#method.init_write_owned) and friends,
+/// which return a [`FlacWriteEncoder`](struct.FlacWriteEncoder.html).
+///
 /// For encoding directly to a file, use [`FlacEncoderConfig::init_file()`](struct.FlacEncoderConfig.html#method.init_file).
-/// Then you must only supply a UTF-8 filename; the encoder will handle all the callbacks
+/// Then you must only supply a filename; the encoder will handle all the callbacks
 /// internally. You may also supply a progress callback for periodic
-/// notification of the encoding progress.
+/// notification of the encoding progress with
+/// [`FlacEncoderConfig::init_file_progress()`](struct.FlacEncoderConfig.html#method.init_file_progress).
 ///
 /// There are three similarly-named init functions for encoding to Ogg
 /// FLAC streams.
@@ -155,9 +171,35 @@ use std::{mem, ptr};
 ///
 /// **Note**:<br />
 /// [`FlacEncoder::finish()`](#method.finish) resets all settings to the constructor defaults.
-#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
-#[repr(transparent)]
-pub struct FlacEncoder<'out>(pub(super) StreamEncoderContainer, pub(super) PhantomData<&'out mut ()>);
+///
+/// **Note**:<br />
+/// The encoder isn't `Send`, since it may be writing into a borrowed stream or calling a progress callback that isn't `Send`.
+/// This holds even for a `FlacEncoder<'static>` from [`init_file()`](struct.FlacEncoderConfig.html#method.init_file)
+/// or [`init_stdout()`](struct.FlacEncoderConfig.html#method.init_stdout), since the lifetime can't tell those apart from
+/// [`init_write()`](struct.FlacEncoderConfig.html#method.init_write) into a leaked `&'static mut WriteWrapper<'static>` over a stream that isn't `Send`.
+///
+/// ```compile_fail
+/// # use flac_bound::FlacEncoder;
+/// # use std::thread;
+/// let enc = FlacEncoder::new().unwrap().init_stdout().unwrap();
+/// thread::spawn(move || enc.finish());
+/// ```
+///
+/// To encode on another thread, move the [`FlacEncoderConfig`](struct.FlacEncoderConfig.html) there and initialise it there,
+/// or use a [`FlacWriteEncoder`](struct.FlacWriteEncoder.html) owning a `Send` stream, like a `File` or `Stdout`:
+///
+/// ```
+/// # use flac_bound::FlacEncoder;
+/// # use std::fs::File;
+/// # use std::thread;
+/// let file = File::create("ЦшЦ-send.flac").unwrap();
+/// let mut enc = FlacEncoder::new().unwrap().channels(1).init_write_seek_owned(file).unwrap();
+/// enc.process(&[&[0; 1024]]).unwrap();
+///
+/// thread::spawn(move || enc.into_inner().unwrap()).join().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct FlacEncoder<'out>(pub(super) StreamEncoderContainer, pub(super) PhantomData<&'out mut ()>, pub(super) Box<EncoderCallbackData<'out>>);
 
 impl<'out> FlacEncoder<'out> {
     /// Create a new stream encoder, in a configuration wrapper, or `None` if one couldn't be allocated.
@@ -165,7 +207,7 @@ impl<'out> FlacEncoder<'out> {
     pub fn new() -> Option<FlacEncoderConfig> {
         let enc = unsafe { FLAC__stream_encoder_new() };
         if !enc.is_null() {
-            Some(FlacEncoderConfig(StreamEncoderContainer(enc)))
+            Some(FlacEncoderConfig(StreamEncoderContainer(enc, Vec::new(), None, Vec::new(), Box::default())))
         } else {
             None
         }
@@ -180,8 +222,34 @@ impl<'out> FlacEncoder<'out> {
     ///
     /// Useful when the stream encoder state is
     /// [`VerifyDecoderError`](enum.FlacEncoderState.html#variant.VerifyDecoderError).
-    pub fn verify_decoder_state(&self) -> FlacEncoderState {
-        FlacEncoderState::try_from(unsafe { FLAC__stream_encoder_get_verify_decoder_state((self.0).0) }).unwrap()
+    pub fn verify_decoder_state(&self) -> FlacDecoderState {
+        FlacDecoderState::try_from(unsafe { FLAC__stream_encoder_get_verify_decoder_state((self.0).0) }).unwrap()
+    }
+
+    /// Get where the verify decoder's output first diverged from the input.
+    ///
+    /// Only meaningful when the encoder state is
+    /// [`VerifyMismatchInAudioData`](enum.FlacEncoderState.html#variant.VerifyMismatchInAudioData);
+    /// this is also included in the [`FlacEncoderError`](struct.FlacEncoderError.html) returned by the call that failed.
+    pub fn verify_decoder_error_stats(&self) -> VerifyMismatch {
+        let mut ret = VerifyMismatch::default();
+        unsafe {
+            FLAC__stream_encoder_get_verify_decoder_error_stats((self.0).0,
+                                                                &mut ret.absolute_sample,
+                                                                &mut ret.frame_number,
+                                                                &mut ret.channel,
+                                                                &mut ret.sample,
+                                                                &mut ret.expected,
+                                                                &mut ret.got)
+        };
+        ret
+    }
+
+    /// Get the current encoder state as a human-readable string,
+    /// resolving the verify decoder's state if the encoder state is
+    /// [`VerifyDecoderError`](enum.FlacEncoderState.html#variant.VerifyDecoderError).
+    pub fn resolved_state_string(&self) -> &'static str {
+        unsafe { CStr::from_ptr(FLAC__stream_encoder_get_resolved_state_string((self.0).0)) }.to_str().unwrap_or("")
     }
 
     /// Submit data for encoding.
@@ -189,7 +257,9 @@ impl<'out> FlacEncoder<'out> {
     /// This version allows you to supply the input data via a slice of
     /// slices, each slice consisting of the same amount of samples as the first one,
     /// representing one channel. The samples need not be block-aligned,
-    /// but each channel should have the same number of samples. Each sample
+    /// but each channel must have the same number of samples, at most `c_uint::MAX`, and there must be exactly as many channels
+    /// as configured with [`FlacEncoderConfig::channels()`](struct.FlacEncoderConfig.html#method.channels),
+    /// otherwise an error with [`invalid_input`](struct.FlacEncoderError.html#structfield.invalid_input) is returned. Each sample
     /// should be a signed integer, right-justified to the resolution set by
     /// [`FlacEncoderConfig::bits_per_sample()`](struct.FlacEncoderConfig.html#method.bits_per_sample). For example, if the
     /// resolution is 16 bits per sample, the samples should all be in the
@@ -200,7 +270,39 @@ impl<'out> FlacEncoder<'out> {
     /// [frame header](https://xiph.org/flac/format.html#frame_header).
     ///
     /// Requires encoder instance to be in OK state.
-    pub fn process(&mut self, buffers: &[&[i32]]) -> Result<(), ()> {
+    ///
+    /// If a callback (the sink or the progress callback) panicked while processing, the panic is resumed here.
+    pub fn process(&mut self, buffers: &[&[i32]]) -> Result<(), FlacEncoderError> {
+        let channels = self.get_channels();
+        if buffers.len() != channels as usize {
+            return Err(self.input_error(FlacEncoderInputError::ChannelCount {
+                expected: channels,
+                got: buffers.len(),
+            }));
+        }
+        if let Some(first) = buffers.first() {
+            if let Some((channel, buf)) = buffers.iter().enumerate().find(|(_, b)| b.len() != first.len()) {
+                return Err(self.input_error(FlacEncoderInputError::ChannelLength {
+                    channel,
+                    expected: first.len(),
+                    got: buf.len(),
+                }));
+            }
+            if c_uint::try_from(first.len()).is_err() {
+                return Err(self.input_error(FlacEncoderInputError::ChannelTooLong { got: first.len() }));
+            }
+        }
+        for (channel, buf) in buffers.iter().enumerate() {
+            if let Some((sample, &value)) = self.find_out_of_range(buf) {
+                return Err(self.input_error(FlacEncoderInputError::SampleOutOfRange {
+                    channel,
+                    sample,
+                    value,
+                    bits_per_sample: self.get_bits_per_sample(),
+                }));
+            }
+        }
+
         if buffers.len() <= 8 {
             let mut buffer = [ptr::null(); 8];
             self.process_impl(&mut buffer, buffers)
@@ -210,17 +312,21 @@ impl<'out> FlacEncoder<'out> {
         }
     }
 
-    fn process_impl(&mut self, buffer: &mut [*const i32], buffers: &[&[i32]]) -> Result<(), ()> {
+    fn process_impl(&mut self, buffer: &mut [*const i32], buffers: &[&[i32]]) -> Result<(), FlacEncoderError> {
+        // Checked to fit by process()
         let samples = buffers.iter().next().map(|b| b.len()).unwrap_or(0) as c_uint;
 
         for (pbfr, sbfr) in buffer.iter_mut().zip(buffers) {
             *pbfr = sbfr.as_ptr();
         }
 
-        if unsafe { FLAC__stream_encoder_process((self.0).0, buffer.as_ptr(), samples) } != 0 {
+        let result = unsafe { FLAC__stream_encoder_process((self.0).0, buffer.as_ptr(), samples) };
+        self.2.resume_panic();
+
+        if result != 0 {
             Ok(())
         } else {
-            Err(())
+            Err(self.error())
         }
     }
 
@@ -231,22 +337,47 @@ impl<'out> FlacEncoder<'out> {
     /// `channel1_sample0`, ... , `channelN_sample0`, `channel0_sample1`, ...).
     /// The samples need not be block-aligned but they must be
     /// sample-aligned, i.e. the first value should be `channel0_sample0`
-    /// and the last value `channelN_sampleM`. Each sample should be a signed
+    /// and the last value `channelN_sampleM`, and the slice must contain exactly `samples_per_channel` samples for each channel.
+    /// Each sample should be a signed
     /// integer, right-justified to the resolution set by
     /// [`FlacEncoderConfig::bits_per_sample()`](struct.FlacEncoderConfig.html#method.bits_per_sample).
     /// For example, if the resolution is 16 bits per sample, the samples should all be in the
     /// range [-32768,32767].
     ///
+    /// If the input doesn't meet these requirements, an error with
+    /// [`invalid_input`](struct.FlacEncoderError.html#structfield.invalid_input) is returned.
+    ///
     /// For applications where channel order is important, channels must
     /// follow the order as described in the
     /// [frame header](https://xiph.org/flac/format.html#frame_header).
     ///
     /// Requires encoder instance to be in OK state.
-    pub fn process_interleaved(&mut self, buffer: &[i32], samples_per_channel: u32) -> Result<(), ()> {
-        if unsafe { FLAC__stream_encoder_process_interleaved((self.0).0, buffer.as_ptr(), samples_per_channel) } != 0 {
+    ///
+    /// If a callback (the sink or the progress callback) panicked while processing, the panic is resumed here.
+    pub fn process_interleaved(&mut self, buffer: &[i32], samples_per_channel: u32) -> Result<(), FlacEncoderError> {
+        let channels = self.get_channels();
+        if buffer.len() as u64 != samples_per_channel as u64 * channels as u64 {
+            return Err(self.input_error(FlacEncoderInputError::InterleavedLength {
+                expected: samples_per_channel as usize * channels as usize,
+                got: buffer.len(),
+            }));
+        }
+        if let Some((idx, &value)) = self.find_out_of_range(buffer) {
+            return Err(self.input_error(FlacEncoderInputError::SampleOutOfRange {
+                channel: idx % channels as usize,
+                sample: idx / channels as usize,
+                value,
+                bits_per_sample: self.get_bits_per_sample(),
+            }));
+        }
+
+        let result = unsafe { FLAC__stream_encoder_process_interleaved((self.0).0, buffer.as_ptr(), samples_per_channel) };
+        self.2.resume_panic();
+
+        if result != 0 {
             Ok(())
         } else {
-            Err(())
+            Err(self.error())
         }
     }
 
@@ -269,18 +400,139 @@ impl<'out> FlacEncoder<'out> {
     ///
     /// This is also called by `drop()`.
     ///
-    /// Returns `Err(self)` if an error occurred processing the last frame, or, if verify
+    /// Returns `Err()` if an error occurred processing the last frame, or, if verify
     /// mode is set (see [`FlacEncoderConfig::verify()`](struct.FlacEncoderConfig.html#method.verify)), there was a
     /// verify mismatch; else the config wrapper.
+    /// The config wrapper is returned alongside the error, too, reset just the same.
+    ///
+    /// If a callback panicked, the panic is resumed here, as with [`process()`](#method.process).
+    ///
+    /// # Examples
     ///
-    /// If `Err()`, caller should check the state with [`state()`](#method.state) for more information about the error.
-    pub fn finish(mut self) -> Result<FlacEncoderConfig, FlacEncoder<'out>> {
-        if unsafe { FLAC__stream_encoder_finish((self.0).0) } != 0 {
-            Ok(FlacEncoderConfig(mem::replace(&mut self.0, StreamEncoderContainer(ptr::null_mut()))))
+    /// ```
+    /// # use flac_bound::{FlacEncoderState, WriteWrapper, FlacEncoder};
+    /// # use std::io::{self, Write};
+    /// # use std::cell::Cell;
+    /// struct Flaky<'a>(&'a Cell<bool>);
+    /// impl<'a> Write for Flaky<'a> {
+    ///     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    ///         if self.0.get() {
+    ///             Err(io::Error::new(io::ErrorKind::Other, "disk full"))
+    ///         } else {
+    ///             Ok(buf.len())
+    ///         }
+    ///     }
+    ///     fn flush(&mut self) -> io::Result<()> {
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let full = Cell::new(false);
+    /// let mut out = Flaky(&full);
+    /// let mut outw = WriteWrapper(&mut out);
+    /// let mut enc = FlacEncoder::new().unwrap().channels(1).init_write(&mut outw).unwrap();
+    /// enc.process(&[&[1, 2, 3]]).unwrap();
+    ///
+    /// full.set(true);
+    /// let (err, conf) = enc.finish().unwrap_err();
+    /// assert_eq!(err.state, FlacEncoderState::ClientError);
+    /// assert_eq!(err.io_error.unwrap().to_string(), "disk full");
+    ///
+    /// // The config is back to the defaults, ready to be used again
+    /// assert_eq!(conf.get_channels(), 2);
+    /// let mut out = vec![];
+    /// let mut outw = WriteWrapper(&mut out);
+    /// let mut enc = conf.channels(1).init_write(&mut outw).unwrap();
+    /// enc.process(&[&[1, 2, 3]]).unwrap();
+    /// enc.finish().unwrap();
+    /// ```
+    #[allow(clippy::result_large_err)]
+    pub fn finish(mut self) -> Result<FlacEncoderConfig, (FlacEncoderError, FlacEncoderConfig)> {
+        let result = unsafe { FLAC__stream_encoder_finish((self.0).0) };
+        self.2.resume_panic();
+
+        let err = if result != 0 {
+            None
         } else {
-            Err(self)
+            let err = self.error();
+            // libFLAC resets the settings either way, but only returns to the uninitialised state on success;
+            // finishing again with the defaults can't fail
+            if self.state() != FlacEncoderState::Uninitialized {
+                unsafe { FLAC__stream_encoder_finish((self.0).0) };
+            }
+            Some(err)
+        };
+
+        let mut enc = mem::replace(&mut self.0, StreamEncoderContainer(ptr::null_mut(), Vec::new(), None, Vec::new(), Box::default()));
+        enc.1.clear();  // Reset to no metadata by libFLAC
+        enc.2 = None;  // along with all other settings
+        enc.3.clear();
+        enc.4 = Box::default();
+        match err {
+            None => Ok(FlacEncoderConfig(enc)),
+            Some(err) => Err((err, FlacEncoderConfig(enc))),
+        }
+    }
+
+    /// Collect what went wrong after a failed call.
+    fn error(&mut self) -> FlacEncoderError {
+        let state = self.state();
+        FlacEncoderError {
+            state,
+            verify_decoder_state: self.verify_decoder_state(),
+            io_error: self.2.io_error.take(),
+            verify_mismatch: if state == FlacEncoderState::VerifyMismatchInAudioData {
+                Some(self.verify_decoder_error_stats())
+            } else {
+                None
+            },
+            invalid_input: None,
         }
     }
+
+    /// Find the first sample that doesn't fit in the configured bits-per-sample.
+    fn find_out_of_range<'b>(&self, samples: &'b [i32]) -> Option<(usize, &'b i32)> {
+        let bits_per_sample = self.get_bits_per_sample();
+        if bits_per_sample >= 32 {
+            return None;
+        }
+
+        let max = (1i32 << (bits_per_sample - 1)) - 1;
+        let min = -max - 1;
+        samples.iter().enumerate().find(|(_, &s)| s < min || s > max)
+    }
+
+    /// Error out on `input` without calling into libFLAC.
+    fn input_error(&mut self, input: FlacEncoderInputError) -> FlacEncoderError {
+        FlacEncoderError { invalid_input: Some(input), ..self.error() }
+    }
+}
+
+// Not derived, since the callback data can't be compared
+impl<'out> PartialEq for FlacEncoder<'out> {
+    fn eq(&self, other: &FlacEncoder<'out>) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<'out> Eq for FlacEncoder<'out> {}
+
+impl<'out> PartialOrd for FlacEncoder<'out> {
+    fn partial_cmp(&self, other: &FlacEncoder<'out>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'out> Ord for FlacEncoder<'out> {
+    fn cmp(&self, other: &FlacEncoder<'out>) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl<'out> Hash for FlacEncoder<'out> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
 }
 
 impl<'out> Drop for FlacEncoder<'out> {
@@ -1,5 +1,6 @@
 #[cfg(feature = "flac")]
-use flac_sys::{FLAC__StreamEncoderInitStatus, FLAC__StreamEncoderInitStatus_FLAC__STREAM_ENCODER_INIT_STATUS_ENCODER_ERROR,
+use flac_sys::{FLAC__StreamEncoderInitStatus, FLAC__StreamEncoderInitStatusString,
+               FLAC__StreamEncoderInitStatus_FLAC__STREAM_ENCODER_INIT_STATUS_ENCODER_ERROR,
                FLAC__StreamEncoderInitStatus_FLAC__STREAM_ENCODER_INIT_STATUS_UNSUPPORTED_CONTAINER,
                FLAC__StreamEncoderInitStatus_FLAC__STREAM_ENCODER_INIT_STATUS_INVALID_CALLBACKS,
                FLAC__StreamEncoderInitStatus_FLAC__STREAM_ENCODER_INIT_STATUS_INVALID_NUMBER_OF_CHANNELS,
@@ -14,7 +15,7 @@ use flac_sys::{FLAC__StreamEncoderInitStatus, FLAC__StreamEncoderInitStatus_FLAC
                FLAC__StreamEncoderInitStatus_FLAC__STREAM_ENCODER_INIT_STATUS_ALREADY_INITIALIZED};
 
 #[cfg(feature = "libflac-nobuild")]
-use libflac_sys::{FLAC__StreamEncoderInitStatus,
+use libflac_sys::{FLAC__StreamEncoderInitStatus, FLAC__StreamEncoderInitStatusString,
                   FLAC__STREAM_ENCODER_INIT_STATUS_ENCODER_ERROR as FLAC__StreamEncoderInitStatus_FLAC__STREAM_ENCODER_INIT_STATUS_ENCODER_ERROR,
                   FLAC__STREAM_ENCODER_INIT_STATUS_UNSUPPORTED_CONTAINER as FLAC__StreamEncoderInitStatus_FLAC__STREAM_ENCODER_INIT_STATUS_UNSUPPORTED_CONTAINER,
                   FLAC__STREAM_ENCODER_INIT_STATUS_INVALID_CALLBACKS as FLAC__StreamEncoderInitStatus_FLAC__STREAM_ENCODER_INIT_STATUS_INVALID_CALLBACKS,
@@ -29,10 +30,58 @@ use libflac_sys::{FLAC__StreamEncoderInitStatus,
                   FLAC__STREAM_ENCODER_INIT_STATUS_INVALID_METADATA as FLAC__StreamEncoderInitStatus_FLAC__STREAM_ENCODER_INIT_STATUS_INVALID_METADATA,
                   FLAC__STREAM_ENCODER_INIT_STATUS_ALREADY_INITIALIZED as FLAC__StreamEncoderInitStatus_FLAC__STREAM_ENCODER_INIT_STATUS_ALREADY_INITIALIZED};
 
+use super::FlacEncoderState;
+use decoder::FlacDecoderState;
+use std::os::raw::{c_char, c_uint};
 use std::convert::TryFrom;
+use std::error::Error;
+use std::ffi::CStr;
+use std::{fmt, ptr, io};
 
 
 /// Possible erroneous return values for the [`FlacEncoderConfig::init_*()`](struct.FlacEncoderConfig.html#method.init_write) functions.
+///
+/// These are returned alongside the config, with its settings and metadata intact, so it can be corrected and initialised again.
+///
+/// If libFLAC got far enough to set the [state](enum.FlacEncoderState.html), e.g. when the output file couldn't be opened,
+/// it resets the settings to their defaults, so they're restored from what was set on the config,
+/// including the apodization and Ogg serial number, which libFLAC has no getters for.
+///
+/// # Examples
+///
+/// ```
+/// # use flac_bound::{FlacEncoderInitError, FlacEncoder};
+/// let (err, conf) = FlacEncoder::new().unwrap().channels(1).sample_rate(8000).blocksize(8).init_file(&"ЦшЦ-init.flac").unwrap_err();
+/// assert_eq!(err, FlacEncoderInitError::InvalidBlockSize);
+///
+/// let (err, conf) = conf.blocksize(0).init_file(&"/nonexistent/ЦшЦ-init.flac").unwrap_err();
+/// assert_eq!(err, FlacEncoderInitError::EncoderError);
+///
+/// let enc = conf.init_file(&"ЦшЦ-init.flac").unwrap();
+/// assert_eq!((enc.get_channels(), enc.get_sample_rate()), (1, 8000));
+/// ```
+///
+/// ```
+/// # use flac_bound::{FlacEncoder, FlacEncoderConfig};
+/// # use std::ffi::CString;
+/// # #[cfg(not(feature = "libflac-noogg"))] {
+/// fn encode(conf: FlacEncoderConfig) -> Vec<u8> {
+///     let mut enc = conf.init_write_ogg_owned(vec![]).unwrap();
+///     enc.process(&[&(0..4096).map(|i| ((i as f64 / 7.0).sin() * 10000.0) as i32 + (i * 7919) % 61).collect::<Vec<_>>()]).unwrap();
+///     enc.into_inner().unwrap()
+/// }
+/// let conf = || FlacEncoder::new().unwrap().channels(1).ogg_serial_number(0x1D).apodization(&CString::new("welch").unwrap());
+///
+/// let (_, retried) = conf().init_file(&"/nonexistent/ЦшЦ-init.flac").unwrap_err();
+/// assert_eq!(encode(retried), encode(conf()));
+/// assert_ne!(encode(FlacEncoder::new().unwrap().channels(1).ogg_serial_number(0x1D)), encode(conf()));
+/// assert_ne!(encode(FlacEncoder::new().unwrap().channels(1).apodization(&CString::new("welch").unwrap())), encode(conf()));
+///
+/// // As is the one implied by the compression level
+/// let (_, retried) = FlacEncoder::new().unwrap().channels(1).compression_level(8).init_file(&"/nonexistent/ЦшЦ-init.flac").unwrap_err();
+/// assert_eq!(encode(retried), encode(FlacEncoder::new().unwrap().channels(1).compression_level(8)));
+/// # }
+/// ```
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(u32)]
 pub enum FlacEncoderInitError {
@@ -81,11 +130,31 @@ pub enum FlacEncoderInitError {
     /// [`FlacEncoderConfig::init_*()`](struct.FlacEncoderConfig.html#method.init_write) was called when the encoder was already initialized, usually because
     /// FLAC__stream_encoder_finish() was not called.
     AlreadyInitialized = FLAC__StreamEncoderInitStatus_FLAC__STREAM_ENCODER_INIT_STATUS_ALREADY_INITIALIZED,
+
+    /// libFLAC rejected one of the settings, listed in
+    /// [`FlacEncoderConfig::rejected_settings()`](struct.FlacEncoderConfig.html#method.rejected_settings),
+    /// so libFLAC wasn't asked to initialise the encoder.
+    ///
+    /// This isn't a libFLAC status, and converts to an out-of-range `FLAC__StreamEncoderInitStatus`.
+    SettingRejected = 0xFFFF_FFFF,
+
+    /// The filename couldn't be handed to libFLAC, since it contains a NUL,
+    /// or, on Windows, where libFLAC expects UTF-8, isn't valid Unicode,
+    /// so libFLAC wasn't asked to initialise the encoder.
+    ///
+    /// This isn't a libFLAC status, and converts to an out-of-range `FLAC__StreamEncoderInitStatus`.
+    InvalidFilename = 0xFFFF_FFFE,
+
+    /// The SEEKTABLE block for the [`FlacEncoderConfig::seek_table()`](struct.FlacEncoderConfig.html#method.seek_table) template
+    /// couldn't be allocated, so libFLAC wasn't asked to initialise the encoder.
+    ///
+    /// This isn't a libFLAC status, and converts to an out-of-range `FLAC__StreamEncoderInitStatus`.
+    MemoryAllocationError = 0xFFFF_FFFD,
 }
 
-impl Into<FLAC__StreamEncoderInitStatus> for FlacEncoderInitError {
-    fn into(self) -> FLAC__StreamEncoderInitStatus {
-        self as FLAC__StreamEncoderInitStatus
+impl From<FlacEncoderInitError> for FLAC__StreamEncoderInitStatus {
+    fn from(val: FlacEncoderInitError) -> FLAC__StreamEncoderInitStatus {
+        val as FLAC__StreamEncoderInitStatus
     }
 }
 
@@ -114,3 +183,350 @@ impl TryFrom<FLAC__StreamEncoderInitStatus> for FlacEncoderInitError {
         })
     }
 }
+
+
+/// Uses libFLAC's `FLAC__StreamEncoderInitStatusString` for libFLAC's statuses, and plain descriptions for the rest.
+impl fmt::Display for FlacEncoderInitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FlacEncoderInitError::SettingRejected => return f.write_str("a setting was rejected: see rejected_settings()"),
+            FlacEncoderInitError::InvalidFilename => return f.write_str("the filename can't be passed to libFLAC"),
+            FlacEncoderInitError::MemoryAllocationError => return f.write_str("the SEEKTABLE block couldn't be allocated"),
+            _ => {}
+        }
+        let name = unsafe { CStr::from_ptr(*(ptr::addr_of!(FLAC__StreamEncoderInitStatusString) as *const *const c_char).add(*self as usize)) };
+        f.write_str(&name.to_string_lossy())
+    }
+}
+
+impl Error for FlacEncoderInitError {}
+
+
+/// Error returned when [`FlacEncoder::process()`](struct.FlacEncoder.html#method.process),
+/// [`process_interleaved()`](struct.FlacEncoder.html#method.process_interleaved), or
+/// [`finish()`](struct.FlacEncoder.html#method.finish) fail.
+#[derive(Debug)]
+pub struct FlacEncoderError {
+    /// The state the encoder was left in.
+    pub state: FlacEncoderState,
+    /// The state of the verify decoder, of interest if `state` is
+    /// [`VerifyDecoderError`](enum.FlacEncoderState.html#variant.VerifyDecoderError).
+    pub verify_decoder_state: FlacDecoderState,
+    /// The error returned by the output sink, if that's what caused the failure.
+    ///
+    /// Only available for the [`init_write*()`](struct.FlacEncoderConfig.html#method.init_write) functions,
+    /// since libFLAC handles I/O itself otherwise.
+    pub io_error: Option<io::Error>,
+    /// Where the verify decoder's output first diverged from the input,
+    /// if `state` is [`VerifyMismatchInAudioData`](enum.FlacEncoderState.html#variant.VerifyMismatchInAudioData).
+    pub verify_mismatch: Option<VerifyMismatch>,
+    /// What was wrong with the input, if it was rejected before being passed to libFLAC.
+    ///
+    /// The encoder state is unaffected by such errors.
+    pub invalid_input: Option<FlacEncoderInputError>,
+}
+
+impl fmt::Display for FlacEncoderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(input) = self.invalid_input.as_ref() {
+            return write!(f, "invalid input: {}", input);
+        }
+
+        write!(f, "{}", self.state)?;
+        if self.state == FlacEncoderState::VerifyDecoderError {
+            write!(f, " ({})", self.verify_decoder_state)?;
+        }
+        if let Some(mismatch) = self.verify_mismatch.as_ref() {
+            write!(f, " {}", mismatch)?;
+        }
+        if let Some(err) = self.io_error.as_ref() {
+            write!(f, ": {}", err)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for FlacEncoderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.io_error.as_ref().map(|err| err as _)
+    }
+}
+
+
+
+/// Input rejected by [`FlacEncoder::process()`](struct.FlacEncoder.html#method.process) and
+/// [`process_interleaved()`](struct.FlacEncoder.html#method.process_interleaved) before it could reach libFLAC.
+///
+/// # Examples
+///
+/// ```
+/// # use flac_bound::{FlacEncoderInputError, FlacEncoder};
+/// let mut enc = FlacEncoder::new().unwrap().channels(2).init_file(&"ЦшЦ-input.flac").unwrap();
+///
+/// assert_eq!(enc.process(&[&[0xA1]]).unwrap_err().invalid_input,
+///            Some(FlacEncoderInputError::ChannelCount { expected: 2, got: 1 }));
+/// assert_eq!(enc.process(&[&[0xA1, 0xA2], &[0xF3]]).unwrap_err().invalid_input,
+///            Some(FlacEncoderInputError::ChannelLength { channel: 1, expected: 2, got: 1 }));
+/// enc.process(&[&[0xA1], &[0xF3]]).unwrap();
+///
+/// assert_eq!(enc.process_interleaved(&[0xA1, 0xF3, 0xA1], 2).unwrap_err().invalid_input,
+///            Some(FlacEncoderInputError::InterleavedLength { expected: 4, got: 3 }));
+/// assert_eq!(enc.process_interleaved(&[0xA1, 0xF3, 0xA1, 0x8000], 2).unwrap_err().invalid_input,
+///            Some(FlacEncoderInputError::SampleOutOfRange { channel: 1, sample: 1, value: 0x8000, bits_per_sample: 16 }));
+/// enc.process_interleaved(&[0xA1, 0xF3, 0xA1, -0x8000], 2).unwrap();
+/// ```
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum FlacEncoderInputError {
+    /// `got` channels were supplied, but the encoder is configured for `expected`.
+    ChannelCount {
+        /// The configured amount of channels.
+        expected: u32,
+        /// The amount of channels supplied.
+        got: usize,
+    },
+    /// Channel `channel` has `got` samples, but the first one has `expected`.
+    ChannelLength {
+        /// Index of the first offending channel.
+        channel: usize,
+        /// The length of the first channel.
+        expected: usize,
+        /// The length of the offending channel.
+        got: usize,
+    },
+    /// Each channel has `got` samples, more than the `c_uint::MAX` libFLAC takes in one call.
+    ChannelTooLong {
+        /// The length of the channels.
+        got: usize,
+    },
+    /// The interleaved buffer has `got` samples, but `samples_per_channel * channels` is `expected`.
+    InterleavedLength {
+        /// `samples_per_channel * channels`.
+        expected: usize,
+        /// The length of the buffer.
+        got: usize,
+    },
+    /// Sample `sample` of channel `channel` is `value`, which doesn't fit in `bits_per_sample` bits.
+    SampleOutOfRange {
+        /// Index of the channel containing the first offending sample.
+        channel: usize,
+        /// Index of the first offending sample within its channel.
+        sample: usize,
+        /// The offending sample.
+        value: i32,
+        /// The configured bits-per-sample.
+        bits_per_sample: u32,
+    },
+}
+
+impl fmt::Display for FlacEncoderInputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FlacEncoderInputError::ChannelCount { expected, got } => write!(f, "{} channels supplied, but encoder configured for {}", got, expected),
+            FlacEncoderInputError::ChannelLength { channel, expected, got } => {
+                write!(f, "channel {} has {} samples, but channel 0 has {}", channel, got, expected)
+            }
+            FlacEncoderInputError::ChannelTooLong { got } => write!(f, "channels have {} samples, more than the maximum of {}", got, c_uint::MAX),
+            FlacEncoderInputError::InterleavedLength { expected, got } => write!(f, "interleaved buffer has {} samples, expected {}", got, expected),
+            FlacEncoderInputError::SampleOutOfRange { channel, sample, value, bits_per_sample } => {
+                write!(f, "sample {} of channel {} is {}, which doesn't fit in {} bits", sample, channel, value, bits_per_sample)
+            }
+        }
+    }
+}
+
+/// A way the settings of a [`FlacEncoderConfig`](struct.FlacEncoderConfig.html) violate the format or the
+/// [Subset](https://xiph.org/flac/format.html#subset), as returned by
+/// [`FlacEncoderConfig::validate()`](struct.FlacEncoderConfig.html#method.validate).
+///
+/// Each corresponds to the [`FlacEncoderInitError`](enum.FlacEncoderInitError.html) initialisation would fail with.
+///
+/// # Examples
+///
+/// ```
+/// # use flac_bound::{FlacEncoderConfigError, FlacEncoder};
+/// let conf = FlacEncoder::new().unwrap().channels(9).sample_rate(44100).blocksize(8192).max_lpc_order(16);
+/// assert_eq!(conf.validate(),
+///            Err(vec![FlacEncoderConfigError::ChannelCount(9),
+///                     FlacEncoderConfigError::SubsetBlockSize { blocksize: 8192, sample_rate: 44100 },
+///                     FlacEncoderConfigError::SubsetMaxLpcOrder { max_lpc_order: 16, sample_rate: 44100 }]));
+///
+/// let conf = conf.channels(2).streamable_subset(false);
+/// assert_eq!(conf.validate(), Ok(()));
+/// ```
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum FlacEncoderConfigError {
+    /// The channel count isn't in `1..=8`.
+    ///
+    /// Corresponds to [`InvalidNumberOfChannels`](enum.FlacEncoderInitError.html#variant.InvalidNumberOfChannels).
+    ChannelCount(u32),
+    /// The bits-per-sample aren't supported by the reference encoder, i.e. aren't in `4..=24` for libFLAC 1.3 and `4..=32` for 1.4.
+    ///
+    /// Corresponds to [`InvalidBitsPerSample`](enum.FlacEncoderInitError.html#variant.InvalidBitsPerSample).
+    BitsPerSample(u32),
+    /// The sample rate isn't valid.
+    ///
+    /// Corresponds to [`InvalidSampleRate`](enum.FlacEncoderInitError.html#variant.InvalidSampleRate).
+    SampleRate(u32),
+    /// The block size isn't in `16..=65535`.
+    ///
+    /// Corresponds to [`InvalidBlockSize`](enum.FlacEncoderInitError.html#variant.InvalidBlockSize).
+    BlockSize(u32),
+    /// The maximum LPC order is over `32`.
+    ///
+    /// Corresponds to [`InvalidMaxLpcOrder`](enum.FlacEncoderInitError.html#variant.InvalidMaxLpcOrder).
+    MaxLpcOrder(u32),
+    /// The block size is smaller than the maximum LPC order.
+    ///
+    /// Corresponds to [`BlockSizeTooSmallForLpcOrder`](enum.FlacEncoderInitError.html#variant.BlockSizeTooSmallForLpcOrder).
+    BlockSizeTooSmallForLpcOrder {
+        /// The block size, or the one the encoder will pick if it's unset.
+        blocksize: u32,
+        /// The maximum LPC order.
+        max_lpc_order: u32,
+    },
+    /// The quantized linear predictor coefficient precision is neither `0` nor in `5..=15`.
+    ///
+    /// Corresponds to [`InvalidQlpCoeffPrecision`](enum.FlacEncoderInitError.html#variant.InvalidQlpCoeffPrecision).
+    QlpCoeffPrecision(u32),
+    /// The quantized linear predictor coefficient precision and bits-per-sample add up to `32` or more,
+    /// which libFLAC 1.3 documents as unsupported; only checked with the `"flac"` back-end.
+    QlpCoeffPrecisionTooHighForBitsPerSample {
+        /// The quantized linear predictor coefficient precision.
+        qlp_coeff_precision: u32,
+        /// The bits-per-sample.
+        bits_per_sample: u32,
+    },
+    /// The sample rate isn't in the Subset.
+    ///
+    /// Corresponds to [`NotStreamable`](enum.FlacEncoderInitError.html#variant.NotStreamable).
+    SubsetSampleRate(u32),
+    /// The bits-per-sample aren't one of the Subset's 8, 12, 16, 20, 24, or 32.
+    ///
+    /// Corresponds to [`NotStreamable`](enum.FlacEncoderInitError.html#variant.NotStreamable).
+    SubsetBitsPerSample(u32),
+    /// The block size is over the Subset's 16384, or 4608 at sample rates up to 48kHz.
+    ///
+    /// Corresponds to [`NotStreamable`](enum.FlacEncoderInitError.html#variant.NotStreamable).
+    SubsetBlockSize {
+        /// The block size, or the one the encoder will pick if it's unset.
+        blocksize: u32,
+        /// The sample rate.
+        sample_rate: u32,
+    },
+    /// The maximum LPC order is over the Subset's 12 at sample rates up to 48kHz.
+    ///
+    /// Corresponds to [`NotStreamable`](enum.FlacEncoderInitError.html#variant.NotStreamable).
+    SubsetMaxLpcOrder {
+        /// The maximum LPC order.
+        max_lpc_order: u32,
+        /// The sample rate.
+        sample_rate: u32,
+    },
+    /// The maximum residual partition order is over the Subset's 8.
+    ///
+    /// Corresponds to [`NotStreamable`](enum.FlacEncoderInitError.html#variant.NotStreamable).
+    SubsetMaxResidualPartitionOrder(u32),
+    /// libFLAC rejected the setting with the contained name.
+    ///
+    /// Corresponds to [`SettingRejected`](enum.FlacEncoderInitError.html#variant.SettingRejected).
+    SettingRejected(&'static str),
+}
+
+impl FlacEncoderConfigError {
+    /// The error initialisation would fail with because of this violation.
+    pub fn init_error(&self) -> FlacEncoderInitError {
+        match self {
+            FlacEncoderConfigError::ChannelCount(_) => FlacEncoderInitError::InvalidNumberOfChannels,
+            FlacEncoderConfigError::BitsPerSample(_) => FlacEncoderInitError::InvalidBitsPerSample,
+            FlacEncoderConfigError::SampleRate(_) => FlacEncoderInitError::InvalidSampleRate,
+            FlacEncoderConfigError::BlockSize(_) => FlacEncoderInitError::InvalidBlockSize,
+            FlacEncoderConfigError::MaxLpcOrder(_) => FlacEncoderInitError::InvalidMaxLpcOrder,
+            FlacEncoderConfigError::BlockSizeTooSmallForLpcOrder { .. } => FlacEncoderInitError::BlockSizeTooSmallForLpcOrder,
+            FlacEncoderConfigError::QlpCoeffPrecision(_) |
+            FlacEncoderConfigError::QlpCoeffPrecisionTooHighForBitsPerSample { .. } => FlacEncoderInitError::InvalidQlpCoeffPrecision,
+            FlacEncoderConfigError::SubsetSampleRate(_) |
+            FlacEncoderConfigError::SubsetBitsPerSample(_) |
+            FlacEncoderConfigError::SubsetBlockSize { .. } |
+            FlacEncoderConfigError::SubsetMaxLpcOrder { .. } |
+            FlacEncoderConfigError::SubsetMaxResidualPartitionOrder(_) => FlacEncoderInitError::NotStreamable,
+            FlacEncoderConfigError::SettingRejected(_) => FlacEncoderInitError::SettingRejected,
+        }
+    }
+}
+
+impl fmt::Display for FlacEncoderConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FlacEncoderConfigError::ChannelCount(channels) => write!(f, "{} channels, must be 1-8", channels),
+            FlacEncoderConfigError::BitsPerSample(bps) => write!(f, "{} bits per sample unsupported", bps),
+            FlacEncoderConfigError::SampleRate(rate) => write!(f, "{}Hz sample rate invalid", rate),
+            FlacEncoderConfigError::BlockSize(blocksize) => write!(f, "block size {}, must be 16-65535", blocksize),
+            FlacEncoderConfigError::MaxLpcOrder(order) => write!(f, "maximum LPC order {}, must be at most 32", order),
+            FlacEncoderConfigError::BlockSizeTooSmallForLpcOrder { blocksize, max_lpc_order } => {
+                write!(f, "block size {} smaller than maximum LPC order {}", blocksize, max_lpc_order)
+            }
+            FlacEncoderConfigError::QlpCoeffPrecision(precision) => write!(f, "QLP coefficient precision {}, must be 0 or 5-15", precision),
+            FlacEncoderConfigError::QlpCoeffPrecisionTooHighForBitsPerSample { qlp_coeff_precision, bits_per_sample } => {
+                write!(f, "QLP coefficient precision {} + {} bits per sample must be under 32", qlp_coeff_precision, bits_per_sample)
+            }
+            FlacEncoderConfigError::SubsetSampleRate(rate) => write!(f, "{}Hz sample rate not in Subset", rate),
+            FlacEncoderConfigError::SubsetBitsPerSample(bps) => write!(f, "{} bits per sample not in Subset", bps),
+            FlacEncoderConfigError::SubsetBlockSize { blocksize, sample_rate } => {
+                write!(f, "block size {} not in Subset at {}Hz", blocksize, sample_rate)
+            }
+            FlacEncoderConfigError::SubsetMaxLpcOrder { max_lpc_order, sample_rate } => {
+                write!(f, "maximum LPC order {} not in Subset at {}Hz", max_lpc_order, sample_rate)
+            }
+            FlacEncoderConfigError::SubsetMaxResidualPartitionOrder(order) => write!(f, "maximum residual partition order {} not in Subset", order),
+            FlacEncoderConfigError::SettingRejected(setting) => write!(f, "setting {} rejected by libFLAC", setting),
+        }
+    }
+}
+
+impl Error for FlacEncoderConfigError {}
+
+/// Where a verify mismatch occurred, as reported by `FLAC__stream_encoder_get_verify_decoder_error_stats()`.
+///
+/// Obtained from [`FlacEncoderError::verify_mismatch`](struct.FlacEncoderError.html#structfield.verify_mismatch) or
+/// [`FlacEncoder::verify_decoder_error_stats()`](struct.FlacEncoder.html#method.verify_decoder_error_stats).
+///
+/// # Examples
+///
+/// ```
+/// # use flac_bound::FlacEncoder;
+/// let mut enc = FlacEncoder::new().unwrap().verify(true).init_file(&"ЦшЦ-verify.flac").unwrap();
+///
+/// if let Err(err) = enc.process_interleaved(&[0xA1, 0xF3], 1) {
+///     if let Some(mismatch) = err.verify_mismatch {
+///         eprintln!("Corruption {}", mismatch);
+///     }
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, Default)]
+pub struct VerifyMismatch {
+    /// The absolute sample number of the mismatch.
+    pub absolute_sample: u64,
+    /// The number of the frame in which the mismatch occurred.
+    pub frame_number: u32,
+    /// The channel in which the mismatch occurred.
+    pub channel: u32,
+    /// The sample number within the frame of the mismatch.
+    pub sample: u32,
+    /// The expected, i.e. input, value.
+    pub expected: i32,
+    /// The value decoded from the encoded stream.
+    pub got: i32,
+}
+
+impl fmt::Display for VerifyMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,
+               "at sample {} (frame {}, channel {}, sample {}): expected {}, got {}",
+               self.absolute_sample,
+               self.frame_number,
+               self.channel,
+               self.sample,
+               self.expected,
+               self.got)
+    }
+}
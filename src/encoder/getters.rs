@@ -0,0 +1,157 @@
+#[cfg(feature = "flac")]
+use flac_sys::{FLAC__stream_encoder_get_verify, FLAC__stream_encoder_get_streamable_subset, FLAC__stream_encoder_get_channels,
+               FLAC__stream_encoder_get_bits_per_sample, FLAC__stream_encoder_get_sample_rate, FLAC__stream_encoder_get_blocksize,
+               FLAC__stream_encoder_get_do_mid_side_stereo, FLAC__stream_encoder_get_loose_mid_side_stereo, FLAC__stream_encoder_get_max_lpc_order,
+               FLAC__stream_encoder_get_qlp_coeff_precision, FLAC__stream_encoder_get_do_qlp_coeff_prec_search, FLAC__stream_encoder_get_do_escape_coding,
+               FLAC__stream_encoder_get_do_exhaustive_model_search, FLAC__stream_encoder_get_min_residual_partition_order,
+               FLAC__stream_encoder_get_max_residual_partition_order, FLAC__stream_encoder_get_rice_parameter_search_dist,
+               FLAC__stream_encoder_get_total_samples_estimate};
+
+#[cfg(feature = "libflac-nobuild")]
+use libflac_sys::{FLAC__stream_encoder_get_verify, FLAC__stream_encoder_get_streamable_subset, FLAC__stream_encoder_get_channels,
+                  FLAC__stream_encoder_get_bits_per_sample, FLAC__stream_encoder_get_sample_rate, FLAC__stream_encoder_get_blocksize,
+                  FLAC__stream_encoder_get_do_mid_side_stereo, FLAC__stream_encoder_get_loose_mid_side_stereo, FLAC__stream_encoder_get_max_lpc_order,
+                  FLAC__stream_encoder_get_qlp_coeff_precision, FLAC__stream_encoder_get_do_qlp_coeff_prec_search,
+                  FLAC__stream_encoder_get_do_escape_coding, FLAC__stream_encoder_get_do_exhaustive_model_search,
+                  FLAC__stream_encoder_get_min_residual_partition_order, FLAC__stream_encoder_get_max_residual_partition_order,
+                  FLAC__stream_encoder_get_rice_parameter_search_dist, FLAC__stream_encoder_get_total_samples_estimate,
+                  FLAC__stream_encoder_get_limit_min_bitrate};
+
+use super::{FlacEncoderConfig, FlacEncoder};
+
+
+/// The same getters are available both before and after initialisation,
+/// so they're generated for both [`FlacEncoderConfig`] and [`FlacEncoder`] from one list.
+macro_rules! encoder_getters {
+    ($($(#[$attr:meta])* fn $name:ident() -> $ret:ty = |$enc:ident| $body:expr;)*) => {
+        impl FlacEncoderConfig {
+            $(
+                $(#[$attr])*
+                pub fn $name(&self) -> $ret {
+                    let $enc = (self.0).0;
+                    unsafe { $body }
+                }
+            )*
+        }
+
+        impl<'out> FlacEncoder<'out> {
+            $(
+                $(#[$attr])*
+                pub fn $name(&self) -> $ret {
+                    let $enc = (self.0).0;
+                    unsafe { $body }
+                }
+            )*
+        }
+    };
+}
+
+encoder_getters! {
+    /// Get the "verify" flag.
+    ///
+    /// See [`FlacEncoderConfig::verify()`](struct.FlacEncoderConfig.html#method.verify).
+    fn get_verify() -> bool = |enc| FLAC__stream_encoder_get_verify(enc) != 0;
+
+    /// Get the Subset flag.
+    ///
+    /// See [`FlacEncoderConfig::streamable_subset()`](struct.FlacEncoderConfig.html#method.streamable_subset).
+    fn get_streamable_subset() -> bool = |enc| FLAC__stream_encoder_get_streamable_subset(enc) != 0;
+
+    /// Get the number of input channels being processed.
+    ///
+    /// See [`FlacEncoderConfig::channels()`](struct.FlacEncoderConfig.html#method.channels).
+    fn get_channels() -> u32 = |enc| FLAC__stream_encoder_get_channels(enc);
+
+    /// Get the input sample resolution setting.
+    ///
+    /// See [`FlacEncoderConfig::bits_per_sample()`](struct.FlacEncoderConfig.html#method.bits_per_sample).
+    fn get_bits_per_sample() -> u32 = |enc| FLAC__stream_encoder_get_bits_per_sample(enc);
+
+    /// Get the input sample rate setting.
+    ///
+    /// See [`FlacEncoderConfig::sample_rate()`](struct.FlacEncoderConfig.html#method.sample_rate).
+    fn get_sample_rate() -> u32 = |enc| FLAC__stream_encoder_get_sample_rate(enc);
+
+    /// Get the blocksize setting; `0` before initialisation means it'll be picked by the encoder.
+    ///
+    /// See [`FlacEncoderConfig::blocksize()`](struct.FlacEncoderConfig.html#method.blocksize).
+    fn get_blocksize() -> u32 = |enc| FLAC__stream_encoder_get_blocksize(enc);
+
+    /// Get the "mid/side stereo coding" flag.
+    ///
+    /// See [`FlacEncoderConfig::do_mid_side_stereo()`](struct.FlacEncoderConfig.html#method.do_mid_side_stereo).
+    fn get_do_mid_side_stereo() -> bool = |enc| FLAC__stream_encoder_get_do_mid_side_stereo(enc) != 0;
+
+    /// Get the "adaptive mid/side switching" flag.
+    ///
+    /// See [`FlacEncoderConfig::loose_mid_side_stereo()`](struct.FlacEncoderConfig.html#method.loose_mid_side_stereo).
+    fn get_loose_mid_side_stereo() -> bool = |enc| FLAC__stream_encoder_get_loose_mid_side_stereo(enc) != 0;
+
+    /// Get the maximum LPC order setting.
+    ///
+    /// See [`FlacEncoderConfig::max_lpc_order()`](struct.FlacEncoderConfig.html#method.max_lpc_order).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use flac_bound::{FlacEncoder, WriteWrapper};
+    /// let conf = FlacEncoder::new().unwrap().compression_level(8);
+    /// assert_eq!(conf.get_max_lpc_order(), 12);
+    /// assert!(conf.get_do_mid_side_stereo());
+    ///
+    /// let mut out = vec![];
+    /// let mut out = WriteWrapper(&mut out);
+    /// let enc = conf.max_lpc_order(10).init_write(&mut out).unwrap();
+    /// assert_eq!(enc.get_max_lpc_order(), 10);
+    /// ```
+    fn get_max_lpc_order() -> u32 = |enc| FLAC__stream_encoder_get_max_lpc_order(enc);
+
+    /// Get the quantized linear predictor coefficient precision setting;
+    /// `0` before initialisation means it'll be picked by the encoder.
+    ///
+    /// See [`FlacEncoderConfig::qlp_coeff_precision()`](struct.FlacEncoderConfig.html#method.qlp_coeff_precision).
+    fn get_qlp_coeff_precision() -> u32 = |enc| FLAC__stream_encoder_get_qlp_coeff_precision(enc);
+
+    /// Get the qlp coefficient precision search flag.
+    ///
+    /// See [`FlacEncoderConfig::do_qlp_coeff_prec_search()`](struct.FlacEncoderConfig.html#method.do_qlp_coeff_prec_search).
+    fn get_do_qlp_coeff_prec_search() -> bool = |enc| FLAC__stream_encoder_get_do_qlp_coeff_prec_search(enc) != 0;
+
+    /// Get the "escape coding" flag.
+    ///
+    /// See [`FlacEncoderConfig::do_escape_coding()`](struct.FlacEncoderConfig.html#method.do_escape_coding).
+    fn get_do_escape_coding() -> bool = |enc| FLAC__stream_encoder_get_do_escape_coding(enc) != 0;
+
+    /// Get the exhaustive model search flag.
+    ///
+    /// See [`FlacEncoderConfig::do_exhaustive_model_search()`](struct.FlacEncoderConfig.html#method.do_exhaustive_model_search).
+    fn get_do_exhaustive_model_search() -> bool = |enc| FLAC__stream_encoder_get_do_exhaustive_model_search(enc) != 0;
+
+    /// Get the minimum residual partition order setting.
+    ///
+    /// See [`FlacEncoderConfig::min_residual_partition_order()`](struct.FlacEncoderConfig.html#method.min_residual_partition_order).
+    fn get_min_residual_partition_order() -> u32 = |enc| FLAC__stream_encoder_get_min_residual_partition_order(enc);
+
+    /// Get the maximum residual partition order setting.
+    ///
+    /// See [`FlacEncoderConfig::max_residual_partition_order()`](struct.FlacEncoderConfig.html#method.max_residual_partition_order).
+    fn get_max_residual_partition_order() -> u32 = |enc| FLAC__stream_encoder_get_max_residual_partition_order(enc);
+
+    /// Get the Rice parameter search distance setting.
+    ///
+    /// See [`FlacEncoderConfig::rice_parameter_search_dist()`](struct.FlacEncoderConfig.html#method.rice_parameter_search_dist).
+    fn get_rice_parameter_search_dist() -> u32 = |enc| FLAC__stream_encoder_get_rice_parameter_search_dist(enc);
+
+    /// Get the previously set estimate of the total samples to be encoded.
+    ///
+    /// The encoder merely mimics back the value given to
+    /// [`FlacEncoderConfig::total_samples_estimate()`](struct.FlacEncoderConfig.html#method.total_samples_estimate)
+    /// since it has no other way of knowing how many samples the client will encode.
+    fn get_total_samples_estimate() -> u64 = |enc| FLAC__stream_encoder_get_total_samples_estimate(enc);
+
+    /// Get the "limit minimum bitrate" flag.
+    ///
+    /// See [`FlacEncoderConfig::set_limit_min_bitrate()`](struct.FlacEncoderConfig.html#method.set_limit_min_bitrate).
+    #[cfg(feature = "libflac-nobuild")]
+    fn get_limit_min_bitrate() -> bool = |enc| FLAC__stream_encoder_get_limit_min_bitrate(enc) != 0;
+}
@@ -1,7 +1,11 @@
+mod apodization;
 mod callbacks;
 #[allow(clippy::module_inception)]
 mod encoder;
 mod config;
+mod owned;
+mod getters;
+mod settings;
 mod state;
 mod error;
 
@@ -11,17 +15,46 @@ use flac_sys::{FLAC__StreamEncoder, FLAC__stream_encoder_delete};
 #[cfg(feature = "libflac-nobuild")]
 use libflac_sys::{FLAC__StreamEncoder, FLAC__stream_encoder_delete};
 
+use metadata::{SeekTableTemplate, FlacMetadata};
+#[cfg(not(feature = "libflac-noogg"))]
+use std::os::raw::c_long;
+use std::hash::{Hash, Hasher};
+use std::cmp::Ordering;
+use std::ffi::CString;
 use std::{mem, ptr};
 
-pub use self::callbacks::{WriteWrapper, flac_encoder_write_write_callback};
-pub use self::error::FlacEncoderInitError;
+pub use self::callbacks::{WriteWrapper, WriteSeekWrapper, WriteSeek, EncoderCallbackData, flac_encoder_write_write_callback,
+                          flac_encoder_write_seek_write_callback, flac_encoder_write_seek_seek_callback, flac_encoder_write_seek_tell_callback,
+                          flac_encoder_progress_callback};
+pub use self::apodization::{ApodizationError, Apodization};
+pub use self::error::{FlacEncoderConfigError, FlacEncoderInputError, FlacEncoderInitError, FlacEncoderError, VerifyMismatch};
 pub use self::config::FlacEncoderConfig;
+pub use self::settings::EncoderSettings;
 pub use self::state::FlacEncoderState;
 pub use self::encoder::FlacEncoder;
+pub use self::owned::FlacWriteEncoder;
 
 
-#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
-struct StreamEncoderContainer(pub *mut FLAC__StreamEncoder);
+/// The raw encoder, the metadata blocks it may be pointing to, which must outlive it,
+/// the seek table template to generate a SEEKTABLE block from at initialisation,
+/// the settings libFLAC rejected, and the ones it has no getters for.
+#[derive(Debug)]
+struct StreamEncoderContainer(pub *mut FLAC__StreamEncoder,
+                              pub Vec<FlacMetadata>,
+                              pub Option<SeekTableTemplate>,
+                              pub Vec<&'static str>,
+                              pub Box<UnreadableSettings>);
+
+/// The settings libFLAC has no getters for, as last set successfully,
+/// to read them into [`EncoderSettings`](struct.EncoderSettings.html) and restore them after a failed initialisation resets the encoder.
+#[derive(Debug, Default)]
+struct UnreadableSettings {
+    /// Only matters for the apodization it implies, unless that's set explicitly afterwards.
+    pub compression_level: Option<u32>,
+    pub apodization: Option<CString>,
+    #[cfg(not(feature = "libflac-noogg"))]
+    pub ogg_serial_number: Option<c_long>,
+}
 
 impl Drop for StreamEncoderContainer {
     fn drop(&mut self) {
@@ -31,3 +64,33 @@ impl Drop for StreamEncoderContainer {
         }
     }
 }
+
+// Identity of the raw encoder, as it was before the container grew
+impl PartialEq for StreamEncoderContainer {
+    fn eq(&self, other: &StreamEncoderContainer) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for StreamEncoderContainer {}
+
+impl PartialOrd for StreamEncoderContainer {
+    fn partial_cmp(&self, other: &StreamEncoderContainer) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for StreamEncoderContainer {
+    fn cmp(&self, other: &StreamEncoderContainer) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl Hash for StreamEncoderContainer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+// libFLAC stream encoders aren't tied to the thread that created them, and the metadata blocks are owned outright
+unsafe impl Send for StreamEncoderContainer {}
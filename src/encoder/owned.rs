@@ -0,0 +1,226 @@
+use super::{FlacEncoderConfig, FlacEncoderInitError, FlacEncoderError, WriteSeekWrapper, WriteWrapper, WriteSeek, FlacEncoder};
+use std::mem::{self, ManuallyDrop};
+use std::io::{Write, Seek};
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+use std::ops::Deref;
+use std::fmt;
+
+
+/// An initialised [`FlacEncoder`](struct.FlacEncoder.html) that owns the stream it's writing to.
+///
+/// Created by [`FlacEncoderConfig::init_write_owned()`](struct.FlacEncoderConfig.html#method.init_write_owned),
+/// [`FlacEncoderConfig::init_write_seek_owned()`](struct.FlacEncoderConfig.html#method.init_write_seek_owned), and
+/// [`FlacEncoderConfig::init_write_ogg_owned()`](struct.FlacEncoderConfig.html#method.init_write_ogg_owned).
+///
+/// Since nothing is borrowed, this can be stored next to whatever else, returned from functions, &c.;
+/// the stream is kept on the heap, so moving the encoder doesn't invalidate what libFLAC is writing to.
+///
+/// Dereferences to the `FlacEncoder` for the getters, and the stream is returned by
+/// [`finish()`](#method.finish) or [`into_inner()`](#method.into_inner).
+/// When dropped, the encoder is finished before the stream is.
+///
+/// # Examples
+///
+/// ```
+/// # use flac_bound::{FlacWriteEncoder, FlacEncoder};
+/// fn encoder() -> FlacWriteEncoder<Vec<u8>> {
+///     FlacEncoder::new().unwrap().channels(1).bits_per_sample(16).init_write_owned(Vec::new()).unwrap()
+/// }
+///
+/// let mut enc = encoder();
+/// enc.process(&[&[0, 1, 2, 3, 2, 1, 0, -1]]).unwrap();
+/// assert_eq!(enc.get_channels(), 1);
+///
+/// let out = enc.into_inner().unwrap();
+/// assert_eq!(&out[..4], b"fLaC");
+/// ```
+///
+/// The encoder is `Send` if the stream is:
+///
+/// ```
+/// # use flac_bound::FlacEncoder;
+/// # use std::io::Cursor;
+/// # use std::thread;
+/// let mut enc = FlacEncoder::new().unwrap().channels(1).init_write_seek_owned(Cursor::new(Vec::new())).unwrap();
+/// enc.process(&[&[0; 1024]]).unwrap();
+///
+/// let out = thread::spawn(move || enc.into_inner().unwrap()).join().unwrap();
+/// assert_eq!(&out.get_ref()[..4], b"fLaC");
+/// ```
+///
+/// ```compile_fail
+/// # use flac_bound::FlacEncoder;
+/// # use std::io::{self, Write};
+/// # use std::cell::RefCell;
+/// # use std::rc::Rc;
+/// # use std::thread;
+/// struct Shared(Rc<RefCell<Vec<u8>>>);
+/// impl Write for Shared {
+///     fn write(&mut self, buf: &[u8]) -> io::Result<usize> { self.0.borrow_mut().write(buf) }
+///     fn flush(&mut self) -> io::Result<()> { Ok(()) }
+/// }
+///
+/// let enc = FlacEncoder::new().unwrap().init_write_owned(Shared(Rc::default())).unwrap();
+/// thread::spawn(move || enc.into_inner());
+/// ```
+pub struct FlacWriteEncoder<W: Write>(ManuallyDrop<FlacEncoder<'static>>, OwnedSink, NonNull<W>, PhantomData<W>);
+
+/// The wrapper libFLAC's client data points to, allocated separately so its address is stable.
+#[derive(Debug, Copy, Clone)]
+enum OwnedSink {
+    Write(*mut WriteWrapper<'static>),
+    WriteSeek(*mut WriteSeekWrapper<'static>),
+}
+
+impl OwnedSink {
+    unsafe fn free(self) {
+        match self {
+            OwnedSink::Write(sink) => drop(Box::from_raw(sink)),
+            OwnedSink::WriteSeek(sink) => drop(Box::from_raw(sink)),
+        }
+    }
+}
+
+impl<W: Write> FlacWriteEncoder<W> {
+    /// Submit data for encoding.
+    ///
+    /// See [`FlacEncoder::process()`](struct.FlacEncoder.html#method.process).
+    pub fn process(&mut self, buffers: &[&[i32]]) -> Result<(), FlacEncoderError> {
+        self.0.process(buffers)
+    }
+
+    /// Submit data for encoding.
+    ///
+    /// See [`FlacEncoder::process_interleaved()`](struct.FlacEncoder.html#method.process_interleaved).
+    pub fn process_interleaved(&mut self, buffer: &[i32], samples_per_channel: u32) -> Result<(), FlacEncoderError> {
+        self.0.process_interleaved(buffer, samples_per_channel)
+    }
+
+    /// Finish the encoding process, and get back the config wrapper and the stream.
+    ///
+    /// See [`FlacEncoder::finish()`](struct.FlacEncoder.html#method.finish);
+    /// the config wrapper and the stream are returned regardless of whether finishing succeeded.
+    #[allow(clippy::result_large_err)]
+    pub fn finish(self) -> Result<(FlacEncoderConfig, W), (FlacEncoderError, FlacEncoderConfig, W)> {
+        let mut this = ManuallyDrop::new(self);
+        let result = unsafe { ManuallyDrop::take(&mut this.0) }.finish();
+
+        let out = unsafe {
+            this.1.free();
+            *Box::from_raw(this.2.as_ptr())
+        };
+        match result {
+            Ok(conf) => Ok((conf, out)),
+            Err((err, conf)) => Err((err, conf, out)),
+        }
+    }
+
+    /// Finish the encoding process, and get back the stream.
+    ///
+    /// Like [`finish()`](#method.finish), but discards the config wrapper.
+    pub fn into_inner(self) -> Result<W, (FlacEncoderError, W)> {
+        self.finish().map(|(_, out)| out).map_err(|(err, _, out)| (err, out))
+    }
+
+    fn new(result: Result<FlacEncoder<'static>, (FlacEncoderInitError, FlacEncoderConfig)>, sink: OwnedSink, out: NonNull<W>)
+           -> Result<FlacWriteEncoder<W>, (FlacEncoderInitError, FlacEncoderConfig, W)> {
+        match result {
+            Ok(enc) => Ok(FlacWriteEncoder(ManuallyDrop::new(enc), sink, out, PhantomData)),
+            Err((err, conf)) => {
+                let out = unsafe {
+                    sink.free();
+                    *Box::from_raw(out.as_ptr())
+                };
+                Err((err, conf, out))
+            }
+        }
+    }
+}
+
+// The callback data only points at the stream and its wrapper, both owned by the encoder
+unsafe impl<W: Write + Send> Send for FlacWriteEncoder<W> {}
+
+impl<W: Write> Deref for FlacWriteEncoder<W> {
+    type Target = FlacEncoder<'static>;
+
+    fn deref(&self) -> &FlacEncoder<'static> {
+        &self.0
+    }
+}
+
+impl<W: Write> fmt::Debug for FlacWriteEncoder<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("FlacWriteEncoder").field(&*self.0).finish()
+    }
+}
+
+impl<W: Write> Drop for FlacWriteEncoder<W> {
+    fn drop(&mut self) {
+        unsafe {
+            ManuallyDrop::drop(&mut self.0);
+            self.1.free();
+            drop(Box::from_raw(self.2.as_ptr()));
+        }
+    }
+}
+
+
+impl FlacEncoderConfig {
+    /// Initialize the encoder instance to encode native FLAC streams into an owned stream.
+    ///
+    /// Like [`init_write()`](#method.init_write), but the stream is moved into the encoder,
+    /// and is returned by [`FlacWriteEncoder::finish()`](struct.FlacWriteEncoder.html#method.finish),
+    /// or alongside the error and the config if initialisation fails.
+    pub fn init_write_owned<W: Write>(self, out: W) -> Result<FlacWriteEncoder<W>, (FlacEncoderInitError, FlacEncoderConfig, W)> {
+        let out = NonNull::from(Box::leak(Box::new(out)));
+        let sink = Box::into_raw(Box::new(WriteWrapper(unsafe { erase_write(out) })));
+        FlacWriteEncoder::new(self.init_write(unsafe { &mut *sink }), OwnedSink::Write(sink), out)
+    }
+
+    /// Initialize the encoder instance to encode native FLAC streams into an owned seekable stream.
+    ///
+    /// Like [`init_write_seek()`](#method.init_write_seek), but the stream is owned as in
+    /// [`init_write_owned()`](#method.init_write_owned).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use flac_bound::FlacEncoder;
+    /// # use std::io::Cursor;
+    /// let mut enc = FlacEncoder::new().unwrap().channels(1).init_write_seek_owned(Cursor::new(Vec::new())).unwrap();
+    /// enc.process(&[&[0; 4096]]).unwrap();
+    /// let out = enc.into_inner().unwrap().into_inner();
+    ///
+    /// // Total sample count is written back into STREAMINFO
+    /// assert_eq!(out[21] & 0x0F, 0);
+    /// assert_eq!(&out[22..26], &4096u32.to_be_bytes());
+    /// ```
+    pub fn init_write_seek_owned<W: Write + Seek>(self, out: W) -> Result<FlacWriteEncoder<W>, (FlacEncoderInitError, FlacEncoderConfig, W)> {
+        let out = NonNull::from(Box::leak(Box::new(out)));
+        let sink = Box::into_raw(Box::new(WriteSeekWrapper(unsafe { erase_write_seek(out) })));
+        FlacWriteEncoder::new(self.init_write_seek(unsafe { &mut *sink }), OwnedSink::WriteSeek(sink), out)
+    }
+
+    /// Initialize the encoder instance to encode Ogg FLAC streams into an owned stream.
+    ///
+    /// Like [`init_write_ogg()`](#method.init_write_ogg), but the stream is owned as in
+    /// [`init_write_owned()`](#method.init_write_owned).
+    #[cfg(not(feature = "libflac-noogg"))]
+    pub fn init_write_ogg_owned<W: Write>(self, out: W) -> Result<FlacWriteEncoder<W>, (FlacEncoderInitError, FlacEncoderConfig, W)> {
+        let out = NonNull::from(Box::leak(Box::new(out)));
+        let sink = Box::into_raw(Box::new(WriteWrapper(unsafe { erase_write(out) })));
+        FlacWriteEncoder::new(self.init_write_ogg(unsafe { &mut *sink }), OwnedSink::Write(sink), out)
+    }
+}
+
+
+/// The stream outlives the encoder since [`FlacWriteEncoder`] frees it last, and `FlacWriteEncoder<W>` can't outlive `W`.
+unsafe fn erase_write<'a, W: Write + 'a>(out: NonNull<W>) -> &'static mut dyn Write {
+    mem::transmute::<&'a mut (dyn Write + 'a), &'static mut (dyn Write + 'static)>(&mut *out.as_ptr())
+}
+
+/// See [`erase_write()`].
+unsafe fn erase_write_seek<'a, W: WriteSeek + 'a>(out: NonNull<W>) -> &'static mut dyn WriteSeek {
+    mem::transmute::<&'a mut (dyn WriteSeek + 'a), &'static mut (dyn WriteSeek + 'static)>(&mut *out.as_ptr())
+}
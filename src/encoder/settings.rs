@@ -0,0 +1,185 @@
+use super::FlacEncoderConfig;
+#[cfg(not(feature = "libflac-noogg"))]
+use std::os::raw::c_long;
+use std::ffi::CString;
+
+
+/// Every knob settable on a [`FlacEncoderConfig`](struct.FlacEncoderConfig.html), as plain data.
+///
+/// Unlike the config, this can be cloned, compared, sent across threads, and, with the `"serde"` feature,
+/// (de)serialised; [`apply()`](#method.apply) it to a fresh config to get an encoder set up the same way.
+///
+/// [`compression_level()`](struct.FlacEncoderConfig.html#method.compression_level) isn't a field of its own,
+/// since it's only a shorthand for setting the other fields:
+/// set it on a config, then extract the settings with [`from_config()`](#method.from_config).
+///
+/// The Ogg serial number and the apodization specification are `None` unless explicitly set on the config,
+/// and aren't touched when applied if `None`.
+///
+/// The [`Default`](#impl-Default) is libFLAC's default configuration.
+///
+/// # Examples
+///
+/// ```
+/// # use flac_bound::{EncoderSettings, FlacEncoder};
+/// assert_eq!(EncoderSettings::from_config(&FlacEncoder::new().unwrap()), EncoderSettings::default());
+///
+/// let mut settings = EncoderSettings::from_config(&FlacEncoder::new().unwrap().compression_level(8));
+/// assert_eq!(settings.max_lpc_order, 12);
+///
+/// settings.channels = 1;
+/// settings.sample_rate = 48000;
+/// settings.apodization = Some("tukey(0.5);flattop".to_string());
+///
+/// let conf = settings.apply(FlacEncoder::new().unwrap());
+/// assert_eq!(conf.get_channels(), 1);
+/// assert_eq!(conf.get_max_lpc_order(), 12);
+/// assert_eq!(settings, EncoderSettings::from_config(&conf));
+/// ```
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct EncoderSettings {
+    /// See [`FlacEncoderConfig::ogg_serial_number()`](struct.FlacEncoderConfig.html#method.ogg_serial_number).
+    ///
+    /// Ogg serial numbers are 32-bit, so this fits in a `c_long` on every platform.
+    #[cfg(not(feature = "libflac-noogg"))]
+    pub ogg_serial_number: Option<i32>,
+    /// See [`FlacEncoderConfig::verify()`](struct.FlacEncoderConfig.html#method.verify).
+    pub verify: bool,
+    /// See [`FlacEncoderConfig::streamable_subset()`](struct.FlacEncoderConfig.html#method.streamable_subset).
+    pub streamable_subset: bool,
+    /// See [`FlacEncoderConfig::channels()`](struct.FlacEncoderConfig.html#method.channels).
+    pub channels: u32,
+    /// See [`FlacEncoderConfig::bits_per_sample()`](struct.FlacEncoderConfig.html#method.bits_per_sample).
+    pub bits_per_sample: u32,
+    /// See [`FlacEncoderConfig::sample_rate()`](struct.FlacEncoderConfig.html#method.sample_rate).
+    pub sample_rate: u32,
+    /// See [`FlacEncoderConfig::blocksize()`](struct.FlacEncoderConfig.html#method.blocksize).
+    pub blocksize: u32,
+    /// See [`FlacEncoderConfig::do_mid_side_stereo()`](struct.FlacEncoderConfig.html#method.do_mid_side_stereo).
+    pub do_mid_side_stereo: bool,
+    /// See [`FlacEncoderConfig::loose_mid_side_stereo()`](struct.FlacEncoderConfig.html#method.loose_mid_side_stereo).
+    pub loose_mid_side_stereo: bool,
+    /// See [`FlacEncoderConfig::apodization()`](struct.FlacEncoderConfig.html#method.apodization).
+    ///
+    /// Anything after a NUL is ignored.
+    pub apodization: Option<String>,
+    /// See [`FlacEncoderConfig::max_lpc_order()`](struct.FlacEncoderConfig.html#method.max_lpc_order).
+    pub max_lpc_order: u32,
+    /// See [`FlacEncoderConfig::qlp_coeff_precision()`](struct.FlacEncoderConfig.html#method.qlp_coeff_precision).
+    pub qlp_coeff_precision: u32,
+    /// See [`FlacEncoderConfig::do_qlp_coeff_prec_search()`](struct.FlacEncoderConfig.html#method.do_qlp_coeff_prec_search).
+    pub do_qlp_coeff_prec_search: bool,
+    /// See [`FlacEncoderConfig::do_escape_coding()`](struct.FlacEncoderConfig.html#method.do_escape_coding).
+    pub do_escape_coding: bool,
+    /// See [`FlacEncoderConfig::do_exhaustive_model_search()`](struct.FlacEncoderConfig.html#method.do_exhaustive_model_search).
+    pub do_exhaustive_model_search: bool,
+    /// See [`FlacEncoderConfig::min_residual_partition_order()`](struct.FlacEncoderConfig.html#method.min_residual_partition_order).
+    pub min_residual_partition_order: u32,
+    /// See [`FlacEncoderConfig::max_residual_partition_order()`](struct.FlacEncoderConfig.html#method.max_residual_partition_order).
+    pub max_residual_partition_order: u32,
+    /// See [`FlacEncoderConfig::set_limit_min_bitrate()`](struct.FlacEncoderConfig.html#method.set_limit_min_bitrate).
+    #[cfg(feature = "libflac-nobuild")]
+    pub limit_min_bitrate: bool,
+    /// See [`FlacEncoderConfig::rice_parameter_search_dist()`](struct.FlacEncoderConfig.html#method.rice_parameter_search_dist).
+    pub rice_parameter_search_dist: u32,
+    /// See [`FlacEncoderConfig::total_samples_estimate()`](struct.FlacEncoderConfig.html#method.total_samples_estimate).
+    pub total_samples_estimate: u64,
+}
+
+impl EncoderSettings {
+    /// Read the current settings of the specified config.
+    pub fn from_config(config: &FlacEncoderConfig) -> EncoderSettings {
+        EncoderSettings {
+            // libogg truncates the serial number to an int anyway
+            #[cfg(not(feature = "libflac-noogg"))]
+            ogg_serial_number: (config.0).4.ogg_serial_number.map(|serial_number| serial_number as i32),
+            verify: config.get_verify(),
+            streamable_subset: config.get_streamable_subset(),
+            channels: config.get_channels(),
+            bits_per_sample: config.get_bits_per_sample(),
+            sample_rate: config.get_sample_rate(),
+            blocksize: config.get_blocksize(),
+            do_mid_side_stereo: config.get_do_mid_side_stereo(),
+            loose_mid_side_stereo: config.get_loose_mid_side_stereo(),
+            apodization: (config.0).4.apodization.as_ref().map(|apodization| apodization.to_string_lossy().into_owned()),
+            max_lpc_order: config.get_max_lpc_order(),
+            qlp_coeff_precision: config.get_qlp_coeff_precision(),
+            do_qlp_coeff_prec_search: config.get_do_qlp_coeff_prec_search(),
+            do_escape_coding: config.get_do_escape_coding(),
+            do_exhaustive_model_search: config.get_do_exhaustive_model_search(),
+            min_residual_partition_order: config.get_min_residual_partition_order(),
+            max_residual_partition_order: config.get_max_residual_partition_order(),
+            #[cfg(feature = "libflac-nobuild")]
+            limit_min_bitrate: config.get_limit_min_bitrate(),
+            rice_parameter_search_dist: config.get_rice_parameter_search_dist(),
+            total_samples_estimate: config.get_total_samples_estimate(),
+        }
+    }
+
+    /// Set all of these settings on the specified config.
+    pub fn apply(&self, mut config: FlacEncoderConfig) -> FlacEncoderConfig {
+        #[cfg(not(feature = "libflac-noogg"))]
+        {
+            if let Some(serial_number) = self.ogg_serial_number {
+                config = config.ogg_serial_number(c_long::from(serial_number));
+            }
+        }
+        config = config.verify(self.verify)
+            .streamable_subset(self.streamable_subset)
+            .channels(self.channels)
+            .bits_per_sample(self.bits_per_sample)
+            .sample_rate(self.sample_rate)
+            .blocksize(self.blocksize)
+            .do_mid_side_stereo(self.do_mid_side_stereo)
+            .loose_mid_side_stereo(self.loose_mid_side_stereo);
+        if let Some(apodization) = self.apodization.as_ref() {
+            let specification = CString::new(apodization.split('\0').next().unwrap()).unwrap();
+            config = config.apodization(&specification);
+        }
+        config = config.max_lpc_order(self.max_lpc_order)
+            .qlp_coeff_precision(self.qlp_coeff_precision)
+            .do_qlp_coeff_prec_search(self.do_qlp_coeff_prec_search)
+            .do_escape_coding(self.do_escape_coding)
+            .do_exhaustive_model_search(self.do_exhaustive_model_search)
+            .min_residual_partition_order(self.min_residual_partition_order)
+            .max_residual_partition_order(self.max_residual_partition_order)
+            .rice_parameter_search_dist(self.rice_parameter_search_dist)
+            .total_samples_estimate(self.total_samples_estimate);
+        #[cfg(feature = "libflac-nobuild")]
+        {
+            config = config.set_limit_min_bitrate(self.limit_min_bitrate);
+        }
+        config
+    }
+}
+
+impl Default for EncoderSettings {
+    fn default() -> EncoderSettings {
+        EncoderSettings {
+            #[cfg(not(feature = "libflac-noogg"))]
+            ogg_serial_number: None,
+            verify: false,
+            streamable_subset: true,
+            channels: 2,
+            bits_per_sample: 16,
+            sample_rate: 44100,
+            blocksize: 0,
+            do_mid_side_stereo: true,
+            loose_mid_side_stereo: false,
+            apodization: None,
+            max_lpc_order: 8,
+            qlp_coeff_precision: 0,
+            do_qlp_coeff_prec_search: false,
+            do_escape_coding: false,
+            do_exhaustive_model_search: false,
+            min_residual_partition_order: 0,
+            max_residual_partition_order: 5,
+            #[cfg(feature = "libflac-nobuild")]
+            limit_min_bitrate: false,
+            rice_parameter_search_dist: 0,
+            total_samples_estimate: 0,
+        }
+    }
+}
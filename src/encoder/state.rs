@@ -1,12 +1,13 @@
 #[cfg(feature = "flac")]
-use flac_sys::{FLAC__StreamEncoderState, FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_OK, FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_UNINITIALIZED,
+use flac_sys::{FLAC__StreamEncoderState, FLAC__StreamEncoderStateString, FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_OK,
+               FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_UNINITIALIZED,
                FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_OGG_ERROR, FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_VERIFY_DECODER_ERROR,
                FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_VERIFY_MISMATCH_IN_AUDIO_DATA, FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_CLIENT_ERROR,
                FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_IO_ERROR, FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_FRAMING_ERROR,
                FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_MEMORY_ALLOCATION_ERROR};
 
 #[cfg(feature = "libflac-nobuild")]
-use libflac_sys::{FLAC__StreamEncoderState,
+use libflac_sys::{FLAC__StreamEncoderState, FLAC__StreamEncoderStateString,
                   FLAC__STREAM_ENCODER_OK as FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_OK,
                   FLAC__STREAM_ENCODER_UNINITIALIZED as FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_UNINITIALIZED,
                   FLAC__STREAM_ENCODER_OGG_ERROR as FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_OGG_ERROR,
@@ -17,7 +18,10 @@ use libflac_sys::{FLAC__StreamEncoderState,
                   FLAC__STREAM_ENCODER_FRAMING_ERROR as FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_FRAMING_ERROR,
                   FLAC__STREAM_ENCODER_MEMORY_ALLOCATION_ERROR as FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_MEMORY_ALLOCATION_ERROR};
 
+use std::os::raw::c_char;
 use std::convert::TryFrom;
+use std::ffi::CStr;
+use std::{fmt, ptr};
 
 
 /// State values for a [`FlacEncoder`](struct.FlacEncoder.html).
@@ -45,7 +49,8 @@ pub enum FlacEncoderState {
     /// [`FlacEncoder::verify_decoder_state()`](struct.FlacEncoder.html#method.verify_decoder_state).
     VerifyDecoderError = FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_VERIFY_DECODER_ERROR,
 
-    /// The verify decoder detected a mismatch between the original audio signal and the decoded audio signal.
+    /// The verify decoder detected a mismatch between the original audio signal and the decoded audio signal;
+    /// check [`FlacEncoder::verify_decoder_error_stats()`](struct.FlacEncoder.html#method.verify_decoder_error_stats).
     VerifyMismatchInAudioData = FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_VERIFY_MISMATCH_IN_AUDIO_DATA,
 
     /// One of the callbacks returned a fatal error.
@@ -61,9 +66,9 @@ pub enum FlacEncoderState {
     MemoryAllocationError = FLAC__StreamEncoderState_FLAC__STREAM_ENCODER_MEMORY_ALLOCATION_ERROR,
 }
 
-impl Into<FLAC__StreamEncoderState> for FlacEncoderState {
-    fn into(self) -> FLAC__StreamEncoderState {
-        self as FLAC__StreamEncoderState
+impl From<FlacEncoderState> for FLAC__StreamEncoderState {
+    fn from(val: FlacEncoderState) -> FLAC__StreamEncoderState {
+        val as FLAC__StreamEncoderState
     }
 }
 
@@ -86,3 +91,11 @@ impl TryFrom<FLAC__StreamEncoderState> for FlacEncoderState {
         })
     }
 }
+
+/// Uses libFLAC's `FLAC__StreamEncoderStateString`.
+impl fmt::Display for FlacEncoderState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = unsafe { CStr::from_ptr(*(ptr::addr_of!(FLAC__StreamEncoderStateString) as *const *const c_char).add(*self as usize)) };
+        f.write_str(&name.to_string_lossy())
+    }
+}
@@ -1,4 +1,4 @@
-//! FLAC encoding via libFLAC FFI
+//! FLAC encoding and decoding via libFLAC FFI
 //!
 //! # Building <!-- also update README -->
 //!
@@ -14,12 +14,15 @@
 //! `libflac-sys` tries to build libFLAC; this is a problem because it (a) doesn't work all that well (at all) under GNU/NT,
 //! and (b) requires the host system to have both CMake and a C toolchain funxional.
 //!
-//! The `"libflac-noogg"` feature will build libFLAC without OGG support.
+//! The `"libflac-noogg"` feature will build libFLAC without OGG support, and remove the Ogg FLAC functions
+//! (`init_*_ogg*()` and `ogg_serial_number()` on both the encoder and the decoder, and the `EncoderSettings::ogg_serial_number` field).
 //!
 //! The `"libflac-nobuild"` feature will still use `libflac-sys` but instruct it to link to the system libFLAC (≥ 1.4.0).
 //!
 //! Downstreams are encouraged to expose these features to the user.
 //!
+//! The `"serde"` feature makes [`EncoderSettings`] (de)serialisable.
+//!
 //! # Examples
 //!
 //! ```
@@ -46,8 +49,9 @@
 //! #       }
 //!         // &c.
 //!     }
-//!     Err(enc) => {
-//!         eprintln!("Encoding failed: {:?}", enc.state());
+//!     Err((err, _conf)) => {
+//!         // The config wrapper is handed back either way
+//!         eprintln!("Encoding failed: {}", err);
 //!     }
 //! };
 //! ```
@@ -65,7 +69,19 @@
 extern crate flac_sys;
 #[cfg(feature="libflac-nobuild")]
 extern crate libflac_sys;
+#[cfg(feature="serde")]
+#[macro_use]
+extern crate serde;
 
+mod metadata;
 mod encoder;
+mod decoder;
+mod util;
 
-pub use encoder::{FlacEncoderInitError, FlacEncoderConfig, FlacEncoderState, WriteWrapper, FlacEncoder};
+pub use metadata::{METADATA_BLOCK_MAX_LENGTH, VorbisCommentError, SeekTableTemplate, FlacMetadataType, CueSheetError, CueSheetTrack, CueSheetIndex,
+                   VorbisComment, PictureError, FlacMetadata, PictureType, CueSheet, Picture};
+pub use encoder::{FlacEncoderConfigError, FlacEncoderInputError, FlacEncoderInitError, FlacEncoderConfig, FlacEncoderError, FlacEncoderState,
+                  ApodizationError, EncoderSettings, VerifyMismatch, WriteWrapper, WriteSeekWrapper, Apodization, WriteSeek, FlacEncoder,
+                  FlacWriteEncoder};
+pub use decoder::{FlacDecoderInitError, FlacDecoderErrorStatus, FlacDecoderConfig, FlacDecoderState, FlacDecoderError, ReadSeekWrapper, ReadSeek,
+                  FlacDecoder};
@@ -0,0 +1,110 @@
+#[cfg(feature = "flac")]
+use flac_sys::{FLAC__StreamMetadata, FLAC__bool, FLAC__metadata_object_new, FLAC__metadata_object_clone, FLAC__metadata_object_delete,
+               FLAC__metadata_object_is_equal, FLAC__metadata_object_application_set_data};
+
+#[cfg(feature = "libflac-nobuild")]
+use libflac_sys::{FLAC__StreamMetadata, FLAC__bool, FLAC__metadata_object_new, FLAC__metadata_object_clone, FLAC__metadata_object_delete,
+                  FLAC__metadata_object_is_equal, FLAC__metadata_object_application_set_data};
+
+use super::FlacMetadataType;
+use std::convert::TryFrom;
+use std::{fmt, mem, ptr};
+
+
+/// An owned [metadata block](https://xiph.org/flac/format.html#metadata_block), allocated by and freed through libFLAC.
+///
+/// Pass these to [`FlacEncoderConfig::metadata()`](struct.FlacEncoderConfig.html#method.metadata) to have them written
+/// after the STREAMINFO block; the encoder will keep them alive for as long as it needs to.
+///
+/// # Examples
+///
+/// ```
+/// # use flac_bound::{FlacEncoder, FlacMetadata};
+/// let mut enc = FlacEncoder::new().unwrap()
+///     .metadata(vec![FlacMetadata::application(*b"test", b"application data").unwrap(), FlacMetadata::padding(1024).unwrap()])
+///     .init_file(&"ЦшЦ-metadata.flac")
+///     .unwrap();
+///
+/// enc.process_interleaved(&[0xA1, 0xF3], 1).unwrap();
+/// ```
+pub struct FlacMetadata(pub(crate) *mut FLAC__StreamMetadata);
+
+impl FlacMetadata {
+    /// Allocate a new, default-initialised, block of the specified type, or `None` if one couldn't be allocated.
+    pub(crate) fn new(tp: FlacMetadataType) -> Option<FlacMetadata> {
+        let obj = unsafe { FLAC__metadata_object_new(tp.into()) };
+        if !obj.is_null() {
+            Some(FlacMetadata(obj))
+        } else {
+            None
+        }
+    }
+
+    /// Create a PADDING block of the specified length in bytes (not including the 4-byte header),
+    /// or `None` if one couldn't be allocated.
+    pub fn padding(length: u32) -> Option<FlacMetadata> {
+        let ret = FlacMetadata::new(FlacMetadataType::Padding)?;
+        unsafe { (*ret.0).length = length };
+        Some(ret)
+    }
+
+    /// Create an APPLICATION block with the specified [registered](https://xiph.org/flac/id.html) application ID
+    /// and data, or `None` if one couldn't be allocated.
+    pub fn application(id: [u8; 4], data: &[u8]) -> Option<FlacMetadata> {
+        let ret = FlacMetadata::new(FlacMetadataType::Application)?;
+        unsafe {
+            (*ret.0).data.application.id = id;
+            if FLAC__metadata_object_application_set_data(ret.0, data.as_ptr() as *mut u8, data.len() as u32, true as FLAC__bool) == 0 {
+                return None;
+            }
+        }
+        Some(ret)
+    }
+
+    /// Duplicate this block, or `None` if the copy couldn't be allocated.
+    pub fn try_clone(&self) -> Option<FlacMetadata> {
+        let obj = unsafe { FLAC__metadata_object_clone(self.0) };
+        if !obj.is_null() {
+            Some(FlacMetadata(obj))
+        } else {
+            None
+        }
+    }
+
+    /// Get the type of this block.
+    pub fn metadata_type(&self) -> FlacMetadataType {
+        FlacMetadataType::try_from(unsafe { (*self.0).type_ }).unwrap()
+    }
+
+    /// Get the length of this block's data in bytes, not including the 4-byte header.
+    pub fn length(&self) -> u32 {
+        unsafe { (*self.0).length }
+    }
+}
+
+// The block is allocated by libFLAC and owned outright, so it can be freed from any thread.
+unsafe impl Send for FlacMetadata {}
+
+impl PartialEq for FlacMetadata {
+    fn eq(&self, other: &FlacMetadata) -> bool {
+        unsafe { FLAC__metadata_object_is_equal(self.0, other.0) != 0 }
+    }
+}
+
+impl fmt::Debug for FlacMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FlacMetadata")
+            .field("metadata_type", &self.metadata_type())
+            .field("length", &self.length())
+            .finish()
+    }
+}
+
+impl Drop for FlacMetadata {
+    fn drop(&mut self) {
+        let ptr = mem::replace(&mut self.0, ptr::null_mut());
+        if !ptr.is_null() {
+            unsafe { FLAC__metadata_object_delete(ptr) };
+        }
+    }
+}
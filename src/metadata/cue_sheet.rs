@@ -0,0 +1,307 @@
+#[cfg(feature = "flac")]
+use flac_sys::{FLAC__bool, FLAC__format_cuesheet_is_legal, FLAC__metadata_object_cuesheet_insert_blank_track,
+               FLAC__metadata_object_cuesheet_track_insert_blank_index};
+
+#[cfg(feature = "libflac-nobuild")]
+use libflac_sys::{FLAC__bool, FLAC__format_cuesheet_is_legal, FLAC__metadata_object_cuesheet_insert_blank_track,
+                  FLAC__metadata_object_cuesheet_track_insert_blank_index};
+
+use super::{FlacMetadataType, FlacMetadata};
+use std::os::raw::c_char;
+use std::error::Error;
+use std::ffi::CStr;
+use std::{fmt, ptr};
+
+
+/// A [CUESHEET](https://xiph.org/flac/format.html#metadata_block_cuesheet) block, i.e. the track layout of e.g. a CD image.
+///
+/// The last track is the lead-out, numbered `170` for CDs and `255` otherwise, at the offset of the end of the stream.
+///
+/// Pass it to [`FlacEncoderConfig::cue_sheet()`](struct.FlacEncoderConfig.html#method.cue_sheet)
+/// to have it written to the stream.
+///
+/// # Examples
+///
+/// ```
+/// # use flac_bound::{FlacEncoder, CueSheet};
+/// let sheet = CueSheet::parse(r#"
+///     CATALOG 1234567890123
+///     FILE "ЦшЦ.wav" WAVE
+///       TRACK 01 AUDIO
+///         ISRC ABCDE1234567
+///         INDEX 01 00:00:00
+///       TRACK 02 AUDIO
+///         INDEX 00 00:01:00
+///         INDEX 01 00:01:30
+/// "#, 44100, 44100 * 3).unwrap();
+///
+/// assert_eq!(sheet.tracks.len(), 3);
+/// assert_eq!(sheet.tracks[1].offset, 44100);
+/// assert_eq!(sheet.tracks[1].indices[1].offset, 44100 * 30 / 75);
+/// assert_eq!(sheet.tracks[2].number, 170);
+/// sheet.validate().unwrap();
+///
+/// let mut enc = FlacEncoder::new().unwrap().cue_sheet(&sheet).init_file(&"ЦшЦ-cuesheet.flac").unwrap();
+/// enc.process_interleaved(&[0; 44100 * 2 * 3], 44100 * 3).unwrap();
+/// ```
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Default)]
+pub struct CueSheet {
+    /// Media catalog number, up to 128 printable ASCII characters; for CDs, 13 digits.
+    pub media_catalog_number: String,
+    /// Amount of lead-in samples; for CDs, at least 2 seconds.
+    pub lead_in: u64,
+    /// Whether the cue sheet corresponds to a CD.
+    pub is_cd: bool,
+    /// The tracks, in order, ending with the lead-out.
+    pub tracks: Vec<CueSheetTrack>,
+}
+
+/// A track in a [`CueSheet`](struct.CueSheet.html).
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Default)]
+pub struct CueSheetTrack {
+    /// Offset of the track in samples, relative to the start of the stream.
+    pub offset: u64,
+    /// Track number, nonzero; for CDs, `1`-`99`.
+    pub number: u8,
+    /// Track [ISRC](https://isrc.ifpi.org), 12 alphanumeric characters, or empty if there is none.
+    pub isrc: String,
+    /// Whether the track is audio, as opposed to data.
+    pub is_audio: bool,
+    /// Whether the track was recorded with pre-emphasis.
+    pub pre_emphasis: bool,
+    /// The index points, in order; empty for the lead-out.
+    pub indices: Vec<CueSheetIndex>,
+}
+
+/// An index point in a [`CueSheetTrack`](struct.CueSheetTrack.html).
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct CueSheetIndex {
+    /// Offset of the index point in samples, relative to the track's offset.
+    pub offset: u64,
+    /// Index point number, the first being `0` or `1` and each subsequent one incremented by `1`.
+    pub number: u8,
+}
+
+impl CueSheet {
+    /// Parse a text [cue sheet](https://en.wikipedia.org/wiki/Cue_sheet_(computing)) referencing a single file,
+    /// converting `MM:SS:FF` timestamps to sample offsets at the specified sample rate,
+    /// which should be the same as the encoder's [`sample_rate()`](struct.FlacEncoderConfig.html#method.sample_rate).
+    ///
+    /// `total_samples` is the length of the stream, at which the lead-out track is placed.
+    ///
+    /// The cue sheet is marked as a CD's if the sample rate is 44.1kHz, and then a 2-second lead-in is added.
+    ///
+    /// `CATALOG`, `TRACK`, `ISRC`, `FLAGS` (`PRE` only), and `INDEX` commands are parsed, all others are ignored.
+    pub fn parse(text: &str, sample_rate: u32, total_samples: u64) -> Result<CueSheet, CueSheetError> {
+        let is_cd = sample_rate == 44100;
+        let mut ret = CueSheet {
+            media_catalog_number: String::new(),
+            lead_in: if is_cd { 2 * 44100 } else { 0 },
+            is_cd,
+            tracks: vec![],
+        };
+
+        let mut seen_file = false;
+        for (line_idx, line) in text.lines().enumerate() {
+            let err = |reason: &str| {
+                CueSheetError::Parse {
+                    line: line_idx + 1,
+                    reason: reason.to_string(),
+                }
+            };
+
+            let mut words = line.split_whitespace();
+            let command = match words.next() {
+                Some(command) => command.to_ascii_uppercase(),
+                None => continue,
+            };
+            let args: Vec<_> = words.collect();
+
+            match &command[..] {
+                "FILE" => {
+                    if seen_file {
+                        return Err(err("cue sheets referencing multiple files are unsupported"));
+                    }
+                    seen_file = true;
+                }
+                "CATALOG" => {
+                    ret.media_catalog_number = args.first().ok_or_else(|| err("CATALOG missing catalog number"))?.to_string();
+                }
+                "TRACK" => {
+                    if args.len() < 2 {
+                        return Err(err("TRACK requires a number and a type"));
+                    }
+                    let number = args[0].parse().map_err(|_| err("invalid TRACK number"))?;
+                    ret.tracks.push(CueSheetTrack {
+                        offset: 0,
+                        number,
+                        isrc: String::new(),
+                        is_audio: args[1].eq_ignore_ascii_case("AUDIO"),
+                        pre_emphasis: false,
+                        indices: vec![],
+                    });
+                }
+                "ISRC" => {
+                    let track = ret.tracks.last_mut().ok_or_else(|| err("ISRC before first TRACK"))?;
+                    track.isrc = args.first().ok_or_else(|| err("ISRC missing code"))?.to_string();
+                }
+                "FLAGS" => {
+                    let track = ret.tracks.last_mut().ok_or_else(|| err("FLAGS before first TRACK"))?;
+                    track.pre_emphasis = args.iter().any(|f| f.eq_ignore_ascii_case("PRE"));
+                }
+                "INDEX" => {
+                    if args.len() < 2 {
+                        return Err(err("INDEX requires a number and a MM:SS:FF timestamp"));
+                    }
+                    let number = args[0].parse().map_err(|_| err("invalid INDEX number"))?;
+                    let offset = CueSheet::parse_timestamp(args[1], sample_rate).ok_or_else(|| err("invalid INDEX timestamp, expected MM:SS:FF"))?;
+
+                    let track = ret.tracks.last_mut().ok_or_else(|| err("INDEX before first TRACK"))?;
+                    // Store absolute offsets for now
+                    track.indices.push(CueSheetIndex {
+                        offset,
+                        number,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        // Tracks start at their earliest index point, relative to which the other index points are
+        for track in &mut ret.tracks {
+            track.offset = track.indices.iter().map(|i| i.offset).min().unwrap_or(0);
+            for index in &mut track.indices {
+                index.offset -= track.offset;
+            }
+        }
+        ret.tracks.push(CueSheetTrack {
+            offset: total_samples,
+            number: if is_cd { 170 } else { 255 },
+            isrc: String::new(),
+            is_audio: true,
+            pre_emphasis: false,
+            indices: vec![],
+        });
+
+        Ok(ret)
+    }
+
+    /// Convert an `MM:SS:FF` timestamp, with 75 frames per second, to samples.
+    fn parse_timestamp(timestamp: &str, sample_rate: u32) -> Option<u64> {
+        let mut parts = timestamp.split(':');
+        let minutes: u64 = parts.next()?.parse().ok()?;
+        let seconds: u64 = parts.next()?.parse().ok()?;
+        let frames: u64 = parts.next()?.parse().ok()?;
+        if parts.next().is_some() || seconds >= 60 || frames >= 75 {
+            return None;
+        }
+
+        Some(((minutes * 60 + seconds) * 75 + frames) * sample_rate as u64 / 75)
+    }
+
+    /// Check if this is a legal cue sheet, as determined by `FLAC__format_cuesheet_is_legal()`,
+    /// also checking CD-DA subset rules if [`is_cd`](#structfield.is_cd) is set.
+    ///
+    /// Cue sheets that fail this will make the encoder initialisation fail with
+    /// [`InvalidMetadata`](enum.FlacEncoderInitError.html#variant.InvalidMetadata).
+    pub fn validate(&self) -> Result<(), CueSheetError> {
+        if self.media_catalog_number.len() > 128 || !self.media_catalog_number.bytes().all(|b| (0x20..=0x7E).contains(&b)) {
+            return Err(CueSheetError::Illegal("media catalog number must be at most 128 printable ASCII characters".to_string()));
+        }
+        if self.tracks.len() > 255 {
+            return Err(CueSheetError::Illegal("cue sheet may have at most 255 tracks".to_string()));
+        }
+        for track in &self.tracks {
+            if !(track.isrc.is_empty() || (track.isrc.len() == 12 && track.isrc.bytes().all(|b| b.is_ascii_alphanumeric()))) {
+                return Err(CueSheetError::Illegal(format!("track {} ISRC must be empty or 12 alphanumeric characters", track.number)));
+            }
+            if track.indices.len() > 255 {
+                return Err(CueSheetError::Illegal(format!("track {} may have at most 255 index points", track.number)));
+            }
+        }
+
+        let block = self.to_metadata().ok_or(CueSheetError::AllocationFailed)?;
+        let mut violation = ptr::null();
+        if unsafe { FLAC__format_cuesheet_is_legal(&(*block.0).data.cue_sheet, self.is_cd as FLAC__bool, &mut violation) } != 0 {
+            Ok(())
+        } else {
+            Err(CueSheetError::Illegal(unsafe { CStr::from_ptr(violation) }.to_string_lossy().into_owned()))
+        }
+    }
+
+    /// Create a metadata block with this cue sheet, or `None` if it couldn't be allocated.
+    ///
+    /// Strings too long to fit are truncated; use [`validate()`](#method.validate) to check the cue sheet beforehand.
+    pub fn to_metadata(&self) -> Option<FlacMetadata> {
+        let ret = FlacMetadata::new(FlacMetadataType::CueSheet)?;
+
+        unsafe {
+            let cue_sheet = &mut (*ret.0).data.cue_sheet;
+            CueSheet::copy_string(&mut cue_sheet.media_catalog_number, &self.media_catalog_number);
+            cue_sheet.lead_in = self.lead_in;
+            cue_sheet.is_cd = self.is_cd as FLAC__bool;
+
+            for (track_idx, track) in self.tracks.iter().enumerate() {
+                if FLAC__metadata_object_cuesheet_insert_blank_track(ret.0, track_idx as u32) == 0 {
+                    return None;
+                }
+                let raw = &mut *(*ret.0).data.cue_sheet.tracks.add(track_idx);
+                raw.offset = track.offset;
+                raw.number = track.number;
+                CueSheet::copy_string(&mut raw.isrc, &track.isrc);
+                raw.set_type(!track.is_audio as _);
+                raw.set_pre_emphasis(track.pre_emphasis as _);
+
+                for (index_idx, index) in track.indices.iter().enumerate() {
+                    if FLAC__metadata_object_cuesheet_track_insert_blank_index(ret.0, track_idx as u32, index_idx as u32) == 0 {
+                        return None;
+                    }
+                    let raw = &mut *(*(*ret.0).data.cue_sheet.tracks.add(track_idx)).indices.add(index_idx);
+                    raw.offset = index.offset;
+                    raw.number = index.number;
+                }
+            }
+        }
+
+        Some(ret)
+    }
+
+    /// Copy as much of `from` as fits into `to`, leaving at least one trailing NUL.
+    fn copy_string(to: &mut [c_char], from: &str) {
+        let len = to.len() - 1;
+        for (t, f) in to[..len].iter_mut().zip(from.bytes().take_while(|&b| b != 0)) {
+            *t = f as c_char;
+        }
+    }
+}
+
+
+/// Errors that can arise when parsing or validating a [`CueSheet`](struct.CueSheet.html).
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum CueSheetError {
+    /// The text cue sheet is malformed at the specified 1-based line.
+    Parse {
+        /// Line number, starting at `1`.
+        line: usize,
+        /// What's wrong with the line.
+        reason: String,
+    },
+
+    /// The cue sheet is illegal for the contained reason.
+    Illegal(String),
+
+    /// The CUESHEET block to check couldn't be allocated.
+    AllocationFailed,
+}
+
+impl fmt::Display for CueSheetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CueSheetError::Parse { line, reason } => write!(f, "cue sheet line {}: {}", line, reason),
+            CueSheetError::Illegal(violation) => write!(f, "illegal cue sheet: {}", violation),
+            CueSheetError::AllocationFailed => f.write_str("CUESHEET block allocation failed"),
+        }
+    }
+}
+
+impl Error for CueSheetError {}
@@ -0,0 +1,67 @@
+#[cfg(feature = "flac")]
+use flac_sys::{FLAC__MetadataType, FLAC__MetadataType_FLAC__METADATA_TYPE_STREAMINFO, FLAC__MetadataType_FLAC__METADATA_TYPE_PADDING,
+               FLAC__MetadataType_FLAC__METADATA_TYPE_APPLICATION, FLAC__MetadataType_FLAC__METADATA_TYPE_SEEKTABLE,
+               FLAC__MetadataType_FLAC__METADATA_TYPE_VORBIS_COMMENT, FLAC__MetadataType_FLAC__METADATA_TYPE_CUESHEET,
+               FLAC__MetadataType_FLAC__METADATA_TYPE_PICTURE};
+
+#[cfg(feature = "libflac-nobuild")]
+use libflac_sys::{FLAC__MetadataType, FLAC__METADATA_TYPE_STREAMINFO as FLAC__MetadataType_FLAC__METADATA_TYPE_STREAMINFO,
+                  FLAC__METADATA_TYPE_PADDING as FLAC__MetadataType_FLAC__METADATA_TYPE_PADDING,
+                  FLAC__METADATA_TYPE_APPLICATION as FLAC__MetadataType_FLAC__METADATA_TYPE_APPLICATION,
+                  FLAC__METADATA_TYPE_SEEKTABLE as FLAC__MetadataType_FLAC__METADATA_TYPE_SEEKTABLE,
+                  FLAC__METADATA_TYPE_VORBIS_COMMENT as FLAC__MetadataType_FLAC__METADATA_TYPE_VORBIS_COMMENT,
+                  FLAC__METADATA_TYPE_CUESHEET as FLAC__MetadataType_FLAC__METADATA_TYPE_CUESHEET,
+                  FLAC__METADATA_TYPE_PICTURE as FLAC__MetadataType_FLAC__METADATA_TYPE_PICTURE};
+
+use std::convert::TryFrom;
+
+
+/// The types of [metadata blocks](https://xiph.org/flac/format.html#metadata_block) a FLAC stream can carry.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum FlacMetadataType {
+    /// [STREAMINFO](https://xiph.org/flac/format.html#metadata_block_streaminfo) block; always present and written by the encoder.
+    StreamInfo = FLAC__MetadataType_FLAC__METADATA_TYPE_STREAMINFO,
+
+    /// [PADDING](https://xiph.org/flac/format.html#metadata_block_padding) block.
+    Padding = FLAC__MetadataType_FLAC__METADATA_TYPE_PADDING,
+
+    /// [APPLICATION](https://xiph.org/flac/format.html#metadata_block_application) block.
+    Application = FLAC__MetadataType_FLAC__METADATA_TYPE_APPLICATION,
+
+    /// [SEEKTABLE](https://xiph.org/flac/format.html#metadata_block_seektable) block.
+    SeekTable = FLAC__MetadataType_FLAC__METADATA_TYPE_SEEKTABLE,
+
+    /// [VORBISCOMMENT](https://xiph.org/flac/format.html#metadata_block_vorbis_comment) block (a.k.a. FLAC tags).
+    VorbisComment = FLAC__MetadataType_FLAC__METADATA_TYPE_VORBIS_COMMENT,
+
+    /// [CUESHEET](https://xiph.org/flac/format.html#metadata_block_cuesheet) block.
+    CueSheet = FLAC__MetadataType_FLAC__METADATA_TYPE_CUESHEET,
+
+    /// [PICTURE](https://xiph.org/flac/format.html#metadata_block_picture) block.
+    Picture = FLAC__MetadataType_FLAC__METADATA_TYPE_PICTURE,
+}
+
+impl From<FlacMetadataType> for FLAC__MetadataType {
+    fn from(val: FlacMetadataType) -> Self {
+        val as FLAC__MetadataType
+    }
+}
+
+impl TryFrom<FLAC__MetadataType> for FlacMetadataType {
+    type Error = ();
+
+    #[allow(non_upper_case_globals)]
+    fn try_from(raw: FLAC__MetadataType) -> Result<FlacMetadataType, ()> {
+        Ok(match raw {
+            FLAC__MetadataType_FLAC__METADATA_TYPE_STREAMINFO => FlacMetadataType::StreamInfo,
+            FLAC__MetadataType_FLAC__METADATA_TYPE_PADDING => FlacMetadataType::Padding,
+            FLAC__MetadataType_FLAC__METADATA_TYPE_APPLICATION => FlacMetadataType::Application,
+            FLAC__MetadataType_FLAC__METADATA_TYPE_SEEKTABLE => FlacMetadataType::SeekTable,
+            FLAC__MetadataType_FLAC__METADATA_TYPE_VORBIS_COMMENT => FlacMetadataType::VorbisComment,
+            FLAC__MetadataType_FLAC__METADATA_TYPE_CUESHEET => FlacMetadataType::CueSheet,
+            FLAC__MetadataType_FLAC__METADATA_TYPE_PICTURE => FlacMetadataType::Picture,
+            _ => return Err(()),
+        })
+    }
+}
@@ -0,0 +1,13 @@
+mod vorbis_comment;
+mod metadata_type;
+mod block;
+mod picture;
+mod seek_table;
+mod cue_sheet;
+
+pub use self::metadata_type::FlacMetadataType;
+pub use self::vorbis_comment::{VorbisCommentError, VorbisComment};
+pub use self::block::FlacMetadata;
+pub use self::seek_table::SeekTableTemplate;
+pub use self::cue_sheet::{CueSheetError, CueSheetTrack, CueSheetIndex, CueSheet};
+pub use self::picture::{METADATA_BLOCK_MAX_LENGTH, PictureError, PictureType, Picture};
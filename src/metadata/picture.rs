@@ -0,0 +1,451 @@
+#[cfg(feature = "flac")]
+use flac_sys::{FLAC__bool, FLAC__StreamMetadata_Picture, FLAC__StreamMetadata_Picture_Type, FLAC__format_picture_is_legal,
+               FLAC__metadata_object_picture_set_mime_type, FLAC__metadata_object_picture_set_description, FLAC__metadata_object_picture_set_data,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_OTHER,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_FILE_ICON_STANDARD,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_FILE_ICON,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_FRONT_COVER,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_BACK_COVER,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_LEAFLET_PAGE,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_MEDIA,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_LEAD_ARTIST,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_ARTIST,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_CONDUCTOR,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_BAND,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_COMPOSER,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_LYRICIST,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_RECORDING_LOCATION,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_DURING_RECORDING,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_DURING_PERFORMANCE,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_VIDEO_SCREEN_CAPTURE,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_FISH,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_ILLUSTRATION,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_BAND_LOGOTYPE,
+               FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_PUBLISHER_LOGOTYPE};
+
+#[cfg(feature = "libflac-nobuild")]
+use libflac_sys::{FLAC__bool, FLAC__StreamMetadata_Picture, FLAC__StreamMetadata_Picture_Type, FLAC__format_picture_is_legal,
+                  FLAC__metadata_object_picture_set_mime_type, FLAC__metadata_object_picture_set_description,
+                  FLAC__metadata_object_picture_set_data,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_OTHER as FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_OTHER,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_FILE_ICON_STANDARD as
+                      FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_FILE_ICON_STANDARD,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_FILE_ICON as FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_FILE_ICON,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_FRONT_COVER as FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_FRONT_COVER,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_BACK_COVER as FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_BACK_COVER,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_LEAFLET_PAGE as FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_LEAFLET_PAGE,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_MEDIA as FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_MEDIA,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_LEAD_ARTIST as FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_LEAD_ARTIST,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_ARTIST as FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_ARTIST,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_CONDUCTOR as FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_CONDUCTOR,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_BAND as FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_BAND,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_COMPOSER as FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_COMPOSER,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_LYRICIST as FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_LYRICIST,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_RECORDING_LOCATION as
+                      FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_RECORDING_LOCATION,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_DURING_RECORDING as
+                      FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_DURING_RECORDING,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_DURING_PERFORMANCE as
+                      FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_DURING_PERFORMANCE,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_VIDEO_SCREEN_CAPTURE as
+                      FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_VIDEO_SCREEN_CAPTURE,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_FISH as FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_FISH,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_ILLUSTRATION as FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_ILLUSTRATION,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_BAND_LOGOTYPE as FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_BAND_LOGOTYPE,
+                  FLAC__STREAM_METADATA_PICTURE_TYPE_PUBLISHER_LOGOTYPE as
+                      FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_PUBLISHER_LOGOTYPE};
+
+use super::{FlacMetadataType, FlacMetadata};
+use std::ffi::{CString, CStr};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::{fmt, ptr};
+
+
+/// Maximum length of a metadata block's data, as limited by the 24-bit length field in its header.
+pub const METADATA_BLOCK_MAX_LENGTH: usize = (1 << 24) - 1;
+
+
+/// A [PICTURE](https://xiph.org/flac/format.html#metadata_block_picture) block, e.g. cover art.
+///
+/// All values are validated on construction, so a `Picture` always fits in a metadata block
+/// and is legal as checked by `FLAC__format_picture_is_legal()`.
+///
+/// Pass it to [`FlacEncoderConfig::picture()`](struct.FlacEncoderConfig.html#method.picture)
+/// to have it written to the stream.
+///
+/// # Examples
+///
+/// ```
+/// # use flac_bound::{FlacEncoder, Picture, PictureType};
+/// // 1x1 8-bit greyscale PNG
+/// let png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x01\0\0\0\x01\x08\x00\x00\x00\x00\x3a\x7e\x9b\x55".to_vec();
+/// let cover = Picture::from_image(PictureType::FrontCover, "ЦшЦ", png).unwrap();
+/// assert_eq!(cover.mime_type(), "image/png");
+/// assert_eq!((cover.width(), cover.height(), cover.depth(), cover.colors()), (1, 1, 8, 0));
+///
+/// let mut enc = FlacEncoder::new().unwrap().picture(&cover).init_file(&"ЦшЦ-picture.flac").unwrap();
+/// enc.process_interleaved(&[0xA1, 0xF3], 1).unwrap();
+/// ```
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Picture {
+    picture_type: PictureType,
+    mime_type: String,
+    description: String,
+    width: u32,
+    height: u32,
+    depth: u32,
+    colors: u32,
+    data: Vec<u8>,
+}
+
+impl Picture {
+    /// Create a picture block with explicitly specified parameters.
+    ///
+    /// `mime_type` must be printable ASCII, and may be `"-->"` to signify that `data` is a URL to the picture.
+    /// `colors` is the amount of colours in an indexed-colour picture, or `0` otherwise.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new<M: Into<String>, D: Into<String>>(picture_type: PictureType, mime_type: M, description: D, width: u32, height: u32, depth: u32,
+                                                 colors: u32, data: Vec<u8>)
+                                                 -> Result<Picture, PictureError> {
+        let ret = Picture {
+            picture_type,
+            mime_type: mime_type.into(),
+            description: description.into(),
+            width,
+            height,
+            depth,
+            colors,
+            data,
+        };
+        ret.validate()?;
+        Ok(ret)
+    }
+
+    /// Create a picture block from a PNG, JPEG, or GIF image,
+    /// filling in the MIME type, dimensions, colour depth, and indexed colour count from its header.
+    pub fn from_image<D: Into<String>>(picture_type: PictureType, description: D, data: Vec<u8>) -> Result<Picture, PictureError> {
+        let (mime_type, width, height, depth, colors) = image_header_png(&data).or_else(|| image_header_jpeg(&data))
+            .or_else(|| image_header_gif(&data))
+            .ok_or(PictureError::UnrecognisedFormat)?;
+        Picture::new(picture_type, mime_type, description, width, height, depth, colors, data)
+    }
+
+    /// Get the type of picture, i.e. what it depicts.
+    pub fn picture_type(&self) -> PictureType {
+        self.picture_type
+    }
+
+    /// Get the picture data's MIME type.
+    pub fn mime_type(&self) -> &str {
+        &self.mime_type
+    }
+
+    /// Get the picture's description.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Get the picture's width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get the picture's height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Get the picture's colour depth in bits-per-pixel.
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Get the amount of colours used by an indexed-colour picture, or `0` for non-indexed pictures.
+    pub fn colors(&self) -> u32 {
+        self.colors
+    }
+
+    /// Get the binary picture data.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Get the length of the block's data, not including the 4-byte header.
+    pub fn length(&self) -> usize {
+        8 * 4 + self.mime_type.len() + self.description.len() + self.data.len()
+    }
+
+    /// Check if this picture fits in a metadata block and is legal, as determined by `FLAC__format_picture_is_legal()`.
+    ///
+    /// This is already done on construction, so it always succeeds for a `Picture` you hold.
+    pub fn validate(&self) -> Result<(), PictureError> {
+        if self.length() > METADATA_BLOCK_MAX_LENGTH {
+            return Err(PictureError::TooLarge(self.length()));
+        }
+
+        let mime_type = CString::new(&self.mime_type[..]).map_err(|_| PictureError::Illegal("MIME type string must not contain NULs".to_string()))?;
+        let description = CString::new(&self.description[..])
+            .map_err(|_| PictureError::Illegal("description string must not contain NULs".to_string()))?;
+        let raw = FLAC__StreamMetadata_Picture {
+            type_: self.picture_type.into(),
+            mime_type: mime_type.as_ptr() as *mut _,
+            description: description.as_ptr() as *mut _,
+            width: self.width,
+            height: self.height,
+            depth: self.depth,
+            colors: self.colors,
+            data_length: self.data.len() as u32,
+            data: self.data.as_ptr() as *mut _,
+        };
+
+        let mut violation = ptr::null();
+        if unsafe { FLAC__format_picture_is_legal(&raw, &mut violation) } != 0 {
+            Ok(())
+        } else {
+            Err(PictureError::Illegal(unsafe { CStr::from_ptr(violation) }.to_string_lossy().into_owned()))
+        }
+    }
+
+    /// Create a metadata block with this picture, or `None` if it couldn't be allocated.
+    pub fn to_metadata(&self) -> Option<FlacMetadata> {
+        let ret = FlacMetadata::new(FlacMetadataType::Picture)?;
+        let mime_type = CString::new(&self.mime_type[..]).unwrap();
+        let description = CString::new(&self.description[..]).unwrap();
+
+        unsafe {
+            let picture = &mut (*ret.0).data.picture;
+            picture.type_ = self.picture_type.into();
+            picture.width = self.width;
+            picture.height = self.height;
+            picture.depth = self.depth;
+            picture.colors = self.colors;
+
+            if FLAC__metadata_object_picture_set_mime_type(ret.0, mime_type.as_ptr() as *mut _, true as FLAC__bool) == 0 ||
+               FLAC__metadata_object_picture_set_description(ret.0, description.as_ptr() as *mut _, true as FLAC__bool) == 0 ||
+               FLAC__metadata_object_picture_set_data(ret.0, self.data.as_ptr() as *mut _, self.data.len() as u32, true as FLAC__bool) == 0 {
+                return None;
+            }
+        }
+
+        Some(ret)
+    }
+}
+
+
+/// `(MIME type, width, height, depth, colors)`
+type ImageHeader = (&'static str, u32, u32, u32, u32);
+
+fn image_header_png(data: &[u8]) -> Option<ImageHeader> {
+    if !data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return None;
+    }
+
+    let mut chunks = &data[8..];
+    let mut header = None;
+    while chunks.len() >= 8 {
+        let length = u32_be(&chunks[0..4]) as usize;
+        let (tag, body) = (&chunks[4..8], chunks.get(8..8 + length)?);
+
+        match tag {
+            b"IHDR" if body.len() >= 10 => {
+                let (bit_depth, color_type) = (body[8] as u32, body[9]);
+                let depth = match color_type {
+                    0 => bit_depth,
+                    2 => bit_depth * 3,
+                    // The palette is always 8 bits per sample, regardless of the bit depth (PNG spec, 11.2.2)
+                    3 => 8 * 3,
+                    4 => bit_depth * 2,
+                    6 => bit_depth * 4,
+                    _ => return None,
+                };
+                if color_type != 3 {
+                    return Some(("image/png", u32_be(&body[0..4]), u32_be(&body[4..8]), depth, 0));
+                }
+                header = Some((u32_be(&body[0..4]), u32_be(&body[4..8]), depth));
+            }
+            b"PLTE" => {
+                let (width, height, depth) = header?;
+                return Some(("image/png", width, height, depth, (length / 3) as u32));
+            }
+            b"IEND" => return None,
+            _ => {}
+        }
+
+        // length, tag, body, CRC
+        chunks = chunks.get(4 + 4 + length + 4..)?;
+    }
+    None
+}
+
+fn image_header_jpeg(data: &[u8]) -> Option<ImageHeader> {
+    if !data.starts_with(b"\xFF\xD8") {
+        return None;
+    }
+
+    let mut segments = &data[2..];
+    loop {
+        // Markers may be preceded by any amount of 0xFF fill bytes
+        let marker_start = segments.iter().position(|&b| b != 0xFF)?;
+        if marker_start == 0 {
+            return None;
+        }
+        let marker = segments[marker_start];
+        segments = &segments[marker_start + 1..];
+
+        match marker {
+            // Standalone markers
+            0x01 | 0xD0..=0xD7 => continue,
+            0xD9 | 0xDA => return None,
+            _ => {}
+        }
+
+        let length = u16_be(segments.get(0..2)?) as usize;
+        let body = segments.get(2..length)?;
+        match marker {
+            // SOFn, except for DHT, JPG, and DAC
+            0xC0..=0xC3 | 0xC5..=0xC7 | 0xC9..=0xCB | 0xCD..=0xCF if body.len() >= 6 => {
+                let (precision, height, width, components) = (body[0] as u32, u16_be(&body[1..3]) as u32, u16_be(&body[3..5]) as u32, body[5] as u32);
+                return Some(("image/jpeg", width, height, precision * components, 0));
+            }
+            _ => segments = &segments[length..],
+        }
+    }
+}
+
+fn image_header_gif(data: &[u8]) -> Option<ImageHeader> {
+    if !(data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a")) || data.len() < 13 {
+        return None;
+    }
+
+    let (width, height, flags) = (u16_le(&data[6..8]) as u32, u16_le(&data[8..10]) as u32, data[10]);
+    let depth = (((flags >> 4) & 0b111) as u32 + 1) * 3;
+    let colors = if flags & 0x80 != 0 { 1 << ((flags & 0b111) + 1) } else { 0 };
+    Some(("image/gif", width, height, depth, colors))
+}
+
+fn u32_be(data: &[u8]) -> u32 {
+    ((data[0] as u32) << 24) | ((data[1] as u32) << 16) | ((data[2] as u32) << 8) | (data[3] as u32)
+}
+
+fn u16_be(data: &[u8]) -> u16 {
+    ((data[0] as u16) << 8) | (data[1] as u16)
+}
+
+fn u16_le(data: &[u8]) -> u16 {
+    ((data[1] as u16) << 8) | (data[0] as u16)
+}
+
+
+/// What a [`Picture`](struct.Picture.html) depicts, as defined by the ID3v2 APIC frame.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum PictureType {
+    /// Other
+    Other = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_OTHER,
+    /// 32x32 pixels 'file icon' (PNG only)
+    FileIconStandard = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_FILE_ICON_STANDARD,
+    /// Other file icon
+    FileIcon = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_FILE_ICON,
+    /// Cover (front)
+    FrontCover = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_FRONT_COVER,
+    /// Cover (back)
+    BackCover = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_BACK_COVER,
+    /// Leaflet page
+    LeafletPage = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_LEAFLET_PAGE,
+    /// Media (e.g. label side of CD)
+    Media = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_MEDIA,
+    /// Lead artist/lead performer/soloist
+    LeadArtist = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_LEAD_ARTIST,
+    /// Artist/performer
+    Artist = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_ARTIST,
+    /// Conductor
+    Conductor = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_CONDUCTOR,
+    /// Band/Orchestra
+    Band = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_BAND,
+    /// Composer
+    Composer = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_COMPOSER,
+    /// Lyricist/text writer
+    Lyricist = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_LYRICIST,
+    /// Recording Location
+    RecordingLocation = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_RECORDING_LOCATION,
+    /// During recording
+    DuringRecording = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_DURING_RECORDING,
+    /// During performance
+    DuringPerformance = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_DURING_PERFORMANCE,
+    /// Movie/video screen capture
+    VideoScreenCapture = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_VIDEO_SCREEN_CAPTURE,
+    /// A bright coloured fish
+    Fish = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_FISH,
+    /// Illustration
+    Illustration = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_ILLUSTRATION,
+    /// Band/artist logotype
+    BandLogotype = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_BAND_LOGOTYPE,
+    /// Publisher/Studio logotype
+    PublisherLogotype = FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_PUBLISHER_LOGOTYPE,
+}
+
+impl From<PictureType> for FLAC__StreamMetadata_Picture_Type {
+    fn from(val: PictureType) -> Self {
+        val as FLAC__StreamMetadata_Picture_Type
+    }
+}
+
+impl TryFrom<FLAC__StreamMetadata_Picture_Type> for PictureType {
+    type Error = ();
+
+    #[allow(non_upper_case_globals)]
+    fn try_from(raw: FLAC__StreamMetadata_Picture_Type) -> Result<PictureType, ()> {
+        Ok(match raw {
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_OTHER => PictureType::Other,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_FILE_ICON_STANDARD => PictureType::FileIconStandard,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_FILE_ICON => PictureType::FileIcon,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_FRONT_COVER => PictureType::FrontCover,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_BACK_COVER => PictureType::BackCover,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_LEAFLET_PAGE => PictureType::LeafletPage,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_MEDIA => PictureType::Media,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_LEAD_ARTIST => PictureType::LeadArtist,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_ARTIST => PictureType::Artist,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_CONDUCTOR => PictureType::Conductor,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_BAND => PictureType::Band,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_COMPOSER => PictureType::Composer,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_LYRICIST => PictureType::Lyricist,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_RECORDING_LOCATION => PictureType::RecordingLocation,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_DURING_RECORDING => PictureType::DuringRecording,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_DURING_PERFORMANCE => PictureType::DuringPerformance,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_VIDEO_SCREEN_CAPTURE => PictureType::VideoScreenCapture,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_FISH => PictureType::Fish,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_ILLUSTRATION => PictureType::Illustration,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_BAND_LOGOTYPE => PictureType::BandLogotype,
+            FLAC__StreamMetadata_Picture_Type_FLAC__STREAM_METADATA_PICTURE_TYPE_PUBLISHER_LOGOTYPE => PictureType::PublisherLogotype,
+            _ => return Err(()),
+        })
+    }
+}
+
+
+/// Errors that can arise when creating a [`Picture`](struct.Picture.html).
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum PictureError {
+    /// The image data is not a PNG, JPEG, or GIF, or its header is malformed.
+    UnrecognisedFormat,
+
+    /// The block would be this many bytes long, which exceeds the maximum of
+    /// [`METADATA_BLOCK_MAX_LENGTH`](constant.METADATA_BLOCK_MAX_LENGTH.html) (16 MiB − 1).
+    TooLarge(usize),
+
+    /// The picture is illegal as determined by `FLAC__format_picture_is_legal()`, for the contained reason.
+    Illegal(String),
+}
+
+impl fmt::Display for PictureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PictureError::UnrecognisedFormat => f.write_str("unrecognised image format"),
+            PictureError::TooLarge(length) => write!(f, "picture block length {} exceeds maximum of {}", length, METADATA_BLOCK_MAX_LENGTH),
+            PictureError::Illegal(violation) => write!(f, "illegal picture: {}", violation),
+        }
+    }
+}
+
+impl Error for PictureError {}
@@ -0,0 +1,107 @@
+#[cfg(feature = "flac")]
+use flac_sys::{FLAC__bool, FLAC__metadata_object_seektable_template_append_points, FLAC__metadata_object_seektable_template_append_spaced_points,
+               FLAC__metadata_object_seektable_template_append_spaced_points_by_samples, FLAC__metadata_object_seektable_template_sort};
+
+#[cfg(feature = "libflac-nobuild")]
+use libflac_sys::{FLAC__bool, FLAC__metadata_object_seektable_template_append_points, FLAC__metadata_object_seektable_template_append_spaced_points,
+                  FLAC__metadata_object_seektable_template_append_spaced_points_by_samples, FLAC__metadata_object_seektable_template_sort};
+
+use super::{FlacMetadataType, FlacMetadata};
+
+
+/// Where to place the points of a [SEEKTABLE](https://xiph.org/flac/format.html#metadata_block_seektable) block.
+///
+/// Pass it to [`FlacEncoderConfig::seek_table()`](struct.FlacEncoderConfig.html#method.seek_table);
+/// the sample numbers are resolved when the encoder is initialised and libFLAC fills in the stream offsets as it encodes.
+///
+/// The offsets can only be filled in if the output is seekable, i.e. with
+/// [`init_write_seek()`](struct.FlacEncoderConfig.html#method.init_write_seek) or the `init_file*()` functions;
+/// otherwise the points are written with zero offsets.
+///
+/// # Examples
+///
+/// ```
+/// # use flac_bound::{FlacEncoder, SeekTableTemplate};
+/// let mut enc = FlacEncoder::new().unwrap()
+///     .sample_rate(8000)
+///     .total_samples_estimate(8000 * 3)
+///     .seek_table(SeekTableTemplate::EverySeconds(1.0))
+///     .init_file(&"ЦшЦ-seektable.flac")
+///     .unwrap();
+///
+/// enc.process_interleaved(&[0; 8000 * 2 * 3], 8000 * 3).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum SeekTableTemplate {
+    /// A point every this many samples.
+    ///
+    /// Requires [`total_samples_estimate()`](struct.FlacEncoderConfig.html#method.total_samples_estimate) to be set.
+    EverySamples(u32),
+    /// A point every this many seconds, at the configured [`sample_rate()`](struct.FlacEncoderConfig.html#method.sample_rate).
+    ///
+    /// Requires [`total_samples_estimate()`](struct.FlacEncoderConfig.html#method.total_samples_estimate) to be set.
+    ///
+    /// Must be finite and non-negative, or [`seek_table()`](struct.FlacEncoderConfig.html#method.seek_table) is rejected.
+    EverySeconds(f64),
+    /// A point at each of the specified sample numbers.
+    Samples(Vec<u64>),
+    /// This many points, evenly spaced across the stream.
+    ///
+    /// Requires [`total_samples_estimate()`](struct.FlacEncoderConfig.html#method.total_samples_estimate) to be set.
+    Spaced(u32),
+}
+
+impl SeekTableTemplate {
+    /// Create a SEEKTABLE block with the points specified by this template, sorted and without duplicates,
+    /// or `None` if it couldn't be allocated.
+    ///
+    /// Templates which require the total sample count produce no points if it's `0`, i.e. unknown,
+    /// as do templates spaced by less than one sample, or by a negative or non-finite amount of seconds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use flac_bound::SeekTableTemplate;
+    /// assert_eq!(SeekTableTemplate::EverySamples(4096).to_metadata(44100, 44100).unwrap().length(), 11 * 18);
+    ///
+    /// assert_eq!(SeekTableTemplate::EverySamples(4096).to_metadata(44100, 0).unwrap().length(), 0);
+    /// assert_eq!(SeekTableTemplate::EverySamples(0).to_metadata(44100, 44100).unwrap().length(), 0);
+    /// assert_eq!(SeekTableTemplate::EverySeconds(0.00001).to_metadata(44100, 44100).unwrap().length(), 0);
+    /// assert_eq!(SeekTableTemplate::EverySeconds(-1.0).to_metadata(44100, 44100).unwrap().length(), 0);
+    /// assert_eq!(SeekTableTemplate::EverySeconds(std::f64::NAN).to_metadata(44100, 44100).unwrap().length(), 0);
+    /// ```
+    pub fn to_metadata(&self, sample_rate: u32, total_samples: u64) -> Option<FlacMetadata> {
+        let ret = FlacMetadata::new(FlacMetadataType::SeekTable)?;
+
+        let result = unsafe {
+            match self {
+                // libFLAC asserts both are non-zero
+                SeekTableTemplate::EverySamples(samples) if *samples == 0 || total_samples == 0 => true as FLAC__bool,
+                SeekTableTemplate::EverySamples(samples) => {
+                    FLAC__metadata_object_seektable_template_append_spaced_points_by_samples(ret.0, *samples, total_samples)
+                }
+                SeekTableTemplate::EverySeconds(seconds) => {
+                    let samples = if seconds.is_finite() && *seconds > 0.0 {
+                        (seconds * sample_rate as f64) as u32
+                    } else {
+                        0
+                    };
+                    if samples == 0 || total_samples == 0 {
+                        true as FLAC__bool
+                    } else {
+                        FLAC__metadata_object_seektable_template_append_spaced_points_by_samples(ret.0, samples, total_samples)
+                    }
+                }
+                SeekTableTemplate::Samples(samples) => {
+                    FLAC__metadata_object_seektable_template_append_points(ret.0, samples.as_ptr() as *mut u64, samples.len() as u32)
+                }
+                SeekTableTemplate::Spaced(points) => FLAC__metadata_object_seektable_template_append_spaced_points(ret.0, *points, total_samples),
+            }
+        };
+        if result == 0 || unsafe { FLAC__metadata_object_seektable_template_sort(ret.0, true as FLAC__bool) } == 0 {
+            return None;
+        }
+
+        Some(ret)
+    }
+}
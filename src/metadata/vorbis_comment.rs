@@ -0,0 +1,142 @@
+#[cfg(feature = "flac")]
+use flac_sys::{FLAC__bool, FLAC__StreamMetadata_VorbisComment_Entry, FLAC__metadata_object_vorbiscomment_append_comment};
+
+#[cfg(feature = "libflac-nobuild")]
+use libflac_sys::{FLAC__bool, FLAC__StreamMetadata_VorbisComment_Entry, FLAC__metadata_object_vorbiscomment_append_comment};
+
+use super::{FlacMetadataType, FlacMetadata};
+use std::error::Error;
+use std::fmt;
+
+
+/// A [VORBIS_COMMENT](https://xiph.org/flac/format.html#metadata_block_vorbis_comment) block, i.e. the stream's tags.
+///
+/// Field names are case-insensitive and may be repeated, values are arbitrary UTF-8.
+/// Comments are written out in the order they were added.
+///
+/// The vendor string isn't settable, since the libFLAC encoder always writes its own.
+///
+/// Pass it to [`FlacEncoderConfig::vorbis_comment()`](struct.FlacEncoderConfig.html#method.vorbis_comment)
+/// to have it written to the stream.
+///
+/// # Examples
+///
+/// ```
+/// # use flac_bound::{FlacEncoder, VorbisComment};
+/// let mut tags = VorbisComment::new();
+/// tags.add("TITLE", "ЦшЦ").unwrap();
+/// tags.add("artist", "наб").unwrap();
+/// tags.add("ARTIST", "Embark Studios").unwrap();
+/// assert_eq!(tags.get("Artist").collect::<Vec<_>>(), ["наб", "Embark Studios"]);
+/// assert!(tags.add("TITLE=", "ЦшЦ").is_err());
+///
+/// let mut enc = FlacEncoder::new().unwrap().vorbis_comment(&tags).init_file(&"ЦшЦ-tags.flac").unwrap();
+/// enc.process_interleaved(&[0xA1, 0xF3], 1).unwrap();
+/// ```
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Default)]
+pub struct VorbisComment {
+    comments: Vec<(String, String)>,
+}
+
+impl VorbisComment {
+    /// Create an empty comment block.
+    pub fn new() -> VorbisComment {
+        VorbisComment::default()
+    }
+
+    /// Append a comment, keeping any other values for the same field.
+    ///
+    /// Returns an error if the field name is empty or contains characters outside of printable ASCII (`0x20`-`0x7D`) or an `'='`.
+    pub fn add<N: Into<String>, V: Into<String>>(&mut self, name: N, value: V) -> Result<(), VorbisCommentError> {
+        let name = name.into();
+        VorbisComment::validate_name(&name)?;
+        self.comments.push((name, value.into()));
+        Ok(())
+    }
+
+    /// Replace all values of the specified field with the specified value.
+    pub fn set<N: Into<String>, V: Into<String>>(&mut self, name: N, value: V) -> Result<(), VorbisCommentError> {
+        let name = name.into();
+        VorbisComment::validate_name(&name)?;
+        self.remove(&name);
+        self.comments.push((name, value.into()));
+        Ok(())
+    }
+
+    /// Remove all values of the specified field, returning how many there were.
+    pub fn remove(&mut self, name: &str) -> usize {
+        let len = self.comments.len();
+        self.comments.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+        len - self.comments.len()
+    }
+
+    /// Get all values of the specified field, in the order they were added.
+    pub fn get<'s>(&'s self, name: &'s str) -> impl Iterator<Item = &'s str> + 's {
+        self.comments.iter().filter(move |(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+
+    /// Iterate over all `(name, value)` pairs, in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.comments.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+    }
+
+    /// Get the amount of comments.
+    pub fn len(&self) -> usize {
+        self.comments.len()
+    }
+
+    /// Check if there are no comments.
+    pub fn is_empty(&self) -> bool {
+        self.comments.is_empty()
+    }
+
+    /// Check if the specified string is a valid field name, as specified in the
+    /// [Vorbis spec](https://xiph.org/vorbis/doc/v-comment.html).
+    pub fn validate_name(name: &str) -> Result<(), VorbisCommentError> {
+        if !name.is_empty() && name.bytes().all(|b| (0x20..=0x7D).contains(&b) && b != b'=') {
+            Ok(())
+        } else {
+            Err(VorbisCommentError::InvalidFieldName(name.to_string()))
+        }
+    }
+
+    /// Create a metadata block with these comments, or `None` if it couldn't be allocated.
+    pub fn to_metadata(&self) -> Option<FlacMetadata> {
+        let ret = FlacMetadata::new(FlacMetadataType::VorbisComment)?;
+
+        for (name, value) in &self.comments {
+            let comment = format!("{}={}", name, value);
+            if unsafe { FLAC__metadata_object_vorbiscomment_append_comment(ret.0, VorbisComment::entry(comment.as_bytes()), true as FLAC__bool) } == 0 {
+                return None;
+            }
+        }
+
+        Some(ret)
+    }
+
+    /// The returned entry borrows `data` and must only be passed to libFLAC for copying.
+    fn entry(data: &[u8]) -> FLAC__StreamMetadata_VorbisComment_Entry {
+        FLAC__StreamMetadata_VorbisComment_Entry {
+            length: data.len() as u32,
+            entry: data.as_ptr() as *mut u8,
+        }
+    }
+}
+
+
+/// Errors that can arise when building a [`VorbisComment`](struct.VorbisComment.html).
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum VorbisCommentError {
+    /// The contained field name is empty or contains characters outside of `0x20`-`0x7D` or an `'='`.
+    InvalidFieldName(String),
+}
+
+impl fmt::Display for VorbisCommentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VorbisCommentError::InvalidFieldName(name) => write!(f, "invalid Vorbis comment field name {:?}", name),
+        }
+    }
+}
+
+impl Error for VorbisCommentError {}
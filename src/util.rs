@@ -0,0 +1,21 @@
+use std::ffi::CString;
+use std::path::Path;
+#[cfg(unix)]
+use std::os::unix::ffi::OsStrExt;
+
+
+/// Convert `path` to what libFLAC's `init_file()` functions expect, or `None` if it can't be represented.
+///
+/// libFLAC `fopen()`s the raw bytes, except on Windows, where it converts the filename from UTF-8.
+#[cfg(unix)]
+pub fn convert_path(path: &Path) -> Option<CString> {
+    CString::new(path.as_os_str().as_bytes()).ok()
+}
+
+/// Convert `path` to what libFLAC's `init_file()` functions expect, or `None` if it can't be represented.
+///
+/// libFLAC `fopen()`s the raw bytes, except on Windows, where it converts the filename from UTF-8.
+#[cfg(not(unix))]
+pub fn convert_path(path: &Path) -> Option<CString> {
+    path.to_str().and_then(|path| CString::new(path).ok())
+}